@@ -1,5 +1,8 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
-use fract::Fract;
+use fract::{Fract, FractWide, RandomFractState};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
 
 fn bench_throughput(c: &mut Criterion) {
     let mut group = c.benchmark_group("throughput");
@@ -39,7 +42,6 @@ fn bench_incremental(c: &mut Criterion) {
 
     for chunk_size in &chunk_sizes {
         let data = vec![0x61u8; total_size];
-        let num_chunks = total_size / chunk_size;
 
         group.throughput(Throughput::Bytes(total_size as u64));
         group.bench_function(format!("incremental_{}_byte_chunks", chunk_size), |b| {
@@ -66,16 +68,63 @@ fn bench_512bit(c: &mut Criterion) {
         group.bench_with_input(format!("hash512_{}_bytes", size), &data, |b, data| {
             b.iter(|| Fract::hash512(black_box(data)));
         });
+        group.bench_with_input(format!("wide_{}_bytes", size), &data, |b, data| {
+            b.iter(|| FractWide::hash(black_box(data)));
+        });
     }
 
     group.finish();
 }
 
+fn bench_hasher(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hasher");
+
+    let fract = RandomFractState::new();
+    let sip = RandomState::new();
+
+    group.bench_function("fract_u64", |b| b.iter(|| fract.hash_one(black_box(42u64))));
+    group.bench_function("siphash_u64", |b| b.iter(|| sip.hash_one(black_box(42u64))));
+
+    for size in [8, 32, 128].iter() {
+        let key = "k".repeat(*size);
+
+        group.bench_with_input(format!("fract_str_{}_bytes", size), &key, |b, key| {
+            b.iter(|| fract.hash_one(black_box(key.as_str())));
+        });
+        group.bench_with_input(format!("siphash_str_{}_bytes", size), &key, |b, key| {
+            b.iter(|| sip.hash_one(black_box(key.as_str())));
+        });
+    }
+
+    let keys: Vec<u64> = (0..1000).collect();
+    group.bench_function("fract_hashmap_insert_1000", |b| {
+        b.iter(|| {
+            let mut map = HashMap::with_capacity_and_hasher(keys.len(), fract.clone());
+            for key in &keys {
+                map.insert(*key, ());
+            }
+            black_box(map)
+        });
+    });
+    group.bench_function("siphash_hashmap_insert_1000", |b| {
+        b.iter(|| {
+            let mut map = HashMap::with_capacity_and_hasher(keys.len(), sip.clone());
+            for key in &keys {
+                map.insert(*key, ());
+            }
+            black_box(map)
+        });
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_throughput,
     bench_latency,
     bench_incremental,
-    bench_512bit
+    bench_512bit,
+    bench_hasher
 );
 criterion_main!(benches);
@@ -0,0 +1,948 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use fract::password;
+use fract::stream::{self, Header, KeySource};
+use fract::{
+    Digest256, Digest512, Fract, FractAead, FractParams, FractPermutation, FractTree, FractWide,
+};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use zeroize::Zeroizing;
+
+/// Size of the read buffer used when streaming files and stdin
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Size of the read buffer in tree mode, enough chunks to keep all cores busy
+const TREE_READ_BUFFER_SIZE: usize = 1024 * FractTree::CHUNK_LEN;
+
+const BANNER: &str = r#"
+    ╔══════════════════════════════════════════════════════════════╗
+    ║                                                              ║
+    ║    ░██████╗░█████╗░░█████╗░██╗░░██╗░█████╗░████████╗░█████╗░  ║
+    ║    ██╔════╝██╔══██╗██╔══██╗██║░░██║██╔══██╗╚══██╔══╝██╔══██╗  ║
+    ║    █████╗░░███████║██║░░╚═╝███████║███████║░░░██║░░░██║░░╚═╝  ║
+    ║    ██╔══╝░░██╔══██║██║░░██╗██╔══██║██╔══██║░░░██║░░░██║░░██╗  ║
+    ║    ██║░░░░░██║░░██║╚█████╔╝██║░░██║██║░░██║░░░██║░░░╚█████╔╝  ║
+    ║    ╚═╝░░░░░╚═╝░░╚═╝░╚════╝░╚═╝░░╚═╝╚═╝░░╚═╝░░░╚═╝░░░░╚════╝░  ║
+    ║                                                              ║
+    ║    Hyperchaotic · Quantum-Resistant · Fast Cryptographic Hash ║
+    ║                                                              ║
+    ║    Author: @morphym                                          ║
+    ║    Version: 0.1.0                                            ║
+    ║                                                              ║
+    ╚══════════════════════════════════════════════════════════════╝
+"#;
+
+/// Fract - A hyperchaotic cryptographic hash function
+///
+/// Calculates Fract-256/512 hashes for files or standard input,
+/// similar to sha256sum or md5sum.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
+    /// File(s) to hash (defaults to stdin if not provided)
+    #[arg(value_name = "FILE")]
+    files: Vec<PathBuf>,
+
+    /// Use 512-bit output mode (enhanced quantum resistance)
+    #[arg(short = '5', long = "512")]
+    use_512: bool,
+
+    /// Use the FRACT-Tree mode (multi-threaded with the `parallel` feature)
+    #[arg(short = 't', long = "tree")]
+    tree: bool,
+
+    /// Check hash values against a list (reads hashes from file)
+    #[arg(short = 'c', long = "check")]
+    check: bool,
+
+    /// Verbose output mode
+    #[arg(short = 'v', long = "verbose")]
+    verbose: bool,
+
+    /// Use binary mode output (backwards compatibility)
+    #[arg(short = 'b', long = "binary")]
+    binary: bool,
+
+    /// Warn about improperly formatted checksum lines
+    #[arg(short = 'w', long = "warn")]
+    warn: bool,
+
+    /// Create a BSD-style checksum: `<algorithm> (<file>) = <hash>`
+    #[arg(long = "tag")]
+    tag: bool,
+
+    /// Print bare hex without the algorithm identifier, as sha256sum does
+    #[arg(long = "untagged", conflicts_with = "tag")]
+    untagged: bool,
+
+    /// Hash algorithm variant (future-proofing)
+    #[arg(value_enum, short = 'a', long = "algorithm", default_value = "fract")]
+    algorithm: Algorithm,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    /// Run built-in benchmarks
+    Bench {
+        /// Test data size in bytes
+        #[arg(short = 's', long = "size", default_value = "1048576")]
+        size: usize,
+
+        /// Number of iterations
+        #[arg(short = 'i', long = "iter", default_value = "100")]
+        iterations: usize,
+
+        /// Test 512-bit mode
+        #[arg(short = '5', long = "512")]
+        use_512: bool,
+
+        /// Test incremental hashing
+        #[arg(short = 'c', long = "chunked")]
+        chunked: bool,
+
+        /// Permutation rounds per block (below 8 is insecure, for analysis only)
+        #[arg(short = 'r', long = "rounds", default_value_t = FractPermutation::ROUNDS)]
+        rounds: usize,
+    },
+
+    /// Encrypt a file with FRACT-AEAD in a chunked streaming format
+    Encrypt {
+        /// Input file (defaults to stdin)
+        #[arg(value_name = "INPUT")]
+        input: Option<PathBuf>,
+
+        /// Output file (defaults to stdout)
+        #[arg(short = 'o', long = "output")]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        key: KeyArgs,
+    },
+
+    /// Decrypt a file produced by `fract encrypt`
+    Decrypt {
+        /// Input file (defaults to stdin)
+        #[arg(value_name = "INPUT")]
+        input: Option<PathBuf>,
+
+        /// Output file (defaults to stdout)
+        #[arg(short = 'o', long = "output")]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        key: KeyArgs,
+    },
+
+    /// Derive key material with the FRACT extract-and-expand KDF
+    Kdf {
+        /// File holding the input keying material (defaults to stdin)
+        #[arg(value_name = "IKM_FILE")]
+        input: Option<PathBuf>,
+
+        /// Salt as hex (empty by default)
+        #[arg(short = 's', long = "salt", default_value = "")]
+        salt: String,
+
+        /// Context and application specific information
+        #[arg(short = 'i', long = "info", default_value = "")]
+        info: String,
+
+        /// Output length in bytes
+        #[arg(short = 'l', long = "length", default_value = "32")]
+        length: usize,
+    },
+
+    /// Hash a password into a PHC string, or verify one
+    Passwd {
+        /// File whose first line is the password (defaults to stdin)
+        #[arg(value_name = "PASSWORD_FILE")]
+        input: Option<PathBuf>,
+
+        /// Verify the password against this PHC string instead of hashing
+        #[arg(long = "verify", value_name = "PHC")]
+        verify: Option<String>,
+
+        /// Memory cost in KiB
+        #[arg(short = 'm', long = "memory", default_value_t = password::Params::DEFAULT.m_cost)]
+        m_cost: u32,
+
+        /// Number of passes over memory
+        #[arg(short = 't', long = "time", default_value_t = password::Params::DEFAULT.t_cost)]
+        t_cost: u32,
+
+        /// Number of parallel lanes
+        #[arg(short = 'p', long = "parallelism", default_value_t = password::Params::DEFAULT.p_cost)]
+        p_cost: u32,
+    },
+}
+
+/// Key material for `encrypt` and `decrypt`
+#[derive(Debug, clap::Args)]
+#[group(required = true, multiple = false)]
+struct KeyArgs {
+    /// File holding a 32-byte key, raw or as 64 hex characters
+    #[arg(short = 'k', long = "key-file")]
+    key_file: Option<PathBuf>,
+
+    /// File whose first line is the passphrase
+    #[arg(short = 'p', long = "passphrase-file")]
+    passphrase_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Algorithm {
+    /// FRACT-256, or FRACT-512 with --512
+    Fract,
+    /// FRACT-Wide: 512-bit output with a 384-bit capacity (no tree mode)
+    FractWide,
+}
+
+impl Algorithm {
+    /// Versioned identifier for the output size and mode
+    fn versioned(&self, use_512: bool, tree: bool) -> fract::Algorithm {
+        match (self, use_512, tree) {
+            (Algorithm::Fract, false, false) => fract::Algorithm::Fract256V1,
+            (Algorithm::Fract, true, false) => fract::Algorithm::Fract512V1,
+            (Algorithm::Fract, false, true) => fract::Algorithm::Fract256TreeV1,
+            (Algorithm::Fract, true, true) => fract::Algorithm::Fract512TreeV1,
+            (Algorithm::FractWide, _, _) => fract::Algorithm::Fract512WideV1,
+        }
+    }
+}
+
+/// Digest computed for a file, or parsed from a check file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileDigest {
+    Fract256(Digest256),
+    Fract512(Digest512),
+}
+
+impl FileDigest {
+    /// Parses a hex digest of the output size of `algorithm`
+    fn parse(hex: &str, algorithm: fract::Algorithm) -> Option<Self> {
+        if algorithm.output_len() == Digest512::LEN {
+            hex.parse().ok().map(FileDigest::Fract512)
+        } else {
+            hex.parse().ok().map(FileDigest::Fract256)
+        }
+    }
+}
+
+/// One entry of a check file
+struct CheckEntry<'a> {
+    algorithm: fract::Algorithm,
+    expected: FileDigest,
+    filename: &'a str,
+}
+
+impl<'a> CheckEntry<'a> {
+    /// Parses a check line in any of the output formats
+    ///
+    /// BSD-style lines name their algorithm, as do hashes tagged as
+    /// `<algorithm>:<hex>`. A bare hex hash is read as `algorithm`; for
+    /// FRACT its length selects 256 or 512 bits, in tree mode with `tree`.
+    fn parse(line: &'a str, algorithm: Algorithm, tree: bool) -> Result<Self, &'static str> {
+        // <algorithm> (<file>) = <hash>
+        if let Some((id, rest)) = line.split_once(" (")
+            && let Ok(algorithm) = id.parse::<fract::Algorithm>()
+        {
+            let (filename, hex) = rest
+                .rsplit_once(") = ")
+                .ok_or("improperly formatted line")?;
+            let expected =
+                FileDigest::parse(hex, algorithm).ok_or("improperly formatted checksum")?;
+            return Ok(Self {
+                algorithm,
+                expected,
+                filename,
+            });
+        }
+
+        // <hash> <mode><file>
+        let (hash, file_spec) = line.split_once(' ').ok_or("improperly formatted line")?;
+        let (algorithm, hex) = if hash.contains(':') {
+            fract::Algorithm::split_tagged(hash).map_err(|_| "unknown algorithm")?
+        } else {
+            let use_512 = hash.len() == 2 * Digest512::LEN;
+            (algorithm.versioned(use_512, tree), hash)
+        };
+        let expected = FileDigest::parse(hex, algorithm).ok_or("improperly formatted checksum")?;
+
+        // Extract filename (handle * or space prefix)
+        let filename = if let Some(name) = file_spec.strip_prefix('*') {
+            name
+        } else {
+            file_spec.trim_start()
+        };
+
+        Ok(Self {
+            algorithm,
+            expected,
+            filename,
+        })
+    }
+}
+
+impl std::fmt::Display for FileDigest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileDigest::Fract256(digest) => digest.fmt(f),
+            FileDigest::Fract512(digest) => digest.fmt(f),
+        }
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+
+    // Show banner if no arguments provided
+    if args.files.is_empty() && args.command.is_none() && !args.check {
+        println!("{}", BANNER);
+        println!("Usage: fract [OPTIONS] [FILE]...");
+        println!("       fract bench [OPTIONS]");
+        println!("       fract encrypt|decrypt [OPTIONS] --key-file <FILE> [INPUT]");
+        println!("       fract kdf [OPTIONS] [IKM_FILE]");
+        println!("       fract passwd [OPTIONS] [PASSWORD_FILE]");
+        println!();
+        println!("Run 'fract --help' for detailed usage information.");
+        std::process::exit(0);
+    }
+
+    if let Some(command) = args.command {
+        match command {
+            Commands::Bench {
+                size,
+                iterations,
+                use_512,
+                chunked,
+                rounds,
+            } => {
+                let params = FractParams::builder()
+                    .rounds(rounds)
+                    .output_len(if use_512 { 64 } else { 32 })
+                    .insecure_reduced_rounds()
+                    .build();
+                match params {
+                    Ok(params) => run_benchmark(size, iterations, &params, chunked),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            Commands::Encrypt { input, output, key } => {
+                if let Err(e) = encrypt_file(input.as_deref(), output.as_deref(), &key) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            Commands::Decrypt { input, output, key } => {
+                if let Err(e) = decrypt_file(input.as_deref(), output.as_deref(), &key) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            Commands::Kdf {
+                input,
+                salt,
+                info,
+                length,
+            } => {
+                if let Err(e) = run_kdf(input.as_deref(), &salt, &info, length) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            Commands::Passwd {
+                input,
+                verify,
+                m_cost,
+                t_cost,
+                p_cost,
+            } => {
+                let result = match verify {
+                    Some(phc) => verify_password(input.as_deref(), &phc),
+                    None => hash_password(input.as_deref(), m_cost, t_cost, p_cost),
+                };
+                if let Err(e) = result {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    } else if matches!(args.algorithm, Algorithm::FractWide) && args.tree {
+        eprintln!("Error: --tree is not available with --algorithm fract-wide");
+        std::process::exit(1);
+    } else if args.check {
+        if args.files.is_empty() {
+            eprintln!("Error: --check requires at least one file argument");
+            std::process::exit(1);
+        }
+        for check_file in &args.files {
+            if let Err(e) = check_hashes(check_file, &args) {
+                eprintln!("Error checking {}: {}", check_file.display(), e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        let result = if args.files.is_empty() {
+            hash_stdin(&args)
+        } else {
+            hash_files(&args.files, &args)
+        };
+
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_benchmark(size: usize, iterations: usize, params: &FractParams, chunked: bool) {
+    let use_512 = params.output_len() == 64;
+
+    println!("=== Fract Benchmark ===");
+    println!("Data size: {} bytes", size);
+    println!("Iterations: {}", iterations);
+    println!("Mode: {}", if use_512 { "512-bit" } else { "256-bit" });
+    println!(
+        "Rounds: {}{}",
+        params.rounds(),
+        if params.is_insecure() {
+            " (reduced, INSECURE)"
+        } else {
+            ""
+        }
+    );
+    println!(
+        "Method: {}",
+        if chunked { "chunked" } else { "single-pass" }
+    );
+    println!();
+
+    // Create test data
+    let data = vec![0x61u8; size]; // 'a' repeated
+
+    // Warmup
+    let mut out = [0u8; 64];
+    let out = &mut out[..params.output_len()];
+    for _ in 0..10 {
+        params.hash_into(&data, out).expect("output length matches");
+    }
+
+    // Benchmark
+    let start = Instant::now();
+    let mut hash = [0u8; 32];
+
+    if chunked {
+        let chunk_size = 4096.min(size);
+        for _ in 0..iterations {
+            let mut hasher = params.hasher();
+            for chunk in data.chunks(chunk_size) {
+                hasher.update(chunk);
+            }
+            if use_512 {
+                let hash512 = hasher.finalize512();
+                hash.copy_from_slice(&hash512[..32]);
+            } else {
+                hash = hasher.finalize();
+            }
+        }
+    } else {
+        for _ in 0..iterations {
+            params.hash_into(&data, out).expect("output length matches");
+            hash.copy_from_slice(&out[..32]);
+        }
+    }
+
+    let elapsed = start.elapsed();
+    let total_bytes = (size * iterations) as f64;
+    let throughput = total_bytes / elapsed.as_secs_f64();
+    let throughput_mib = throughput / (1024.0 * 1024.0);
+
+    println!("Total time: {:?}", elapsed);
+    println!("Throughput: {:.2} MiB/s", throughput_mib);
+    println!("Last hash: {}", hex::encode(&hash[..8]));
+    println!();
+
+    // Additional stats
+    println!("=== Additional Stats ===");
+    println!("Bytes processed: {}", total_bytes as usize);
+    println!(
+        "Nanoseconds per byte: {:.2}",
+        (elapsed.as_nanos() as f64) / total_bytes
+    );
+    if !use_512 {
+        println!(
+            "Cycles/byte (est. at 3GHz): {:.2}",
+            (elapsed.as_nanos() as f64 * 3.0) / total_bytes
+        );
+    }
+}
+
+/// Streams a reader through the hasher without buffering it in memory
+///
+/// The read buffer is wiped afterwards, as is the hasher state.
+fn hash_reader<R: Read>(reader: R, algorithm: fract::Algorithm) -> io::Result<FileDigest> {
+    let use_512 = algorithm.output_len() == Digest512::LEN;
+    if algorithm.is_tree() {
+        return tree_hash_reader(reader, use_512);
+    }
+
+    if algorithm == fract::Algorithm::Fract512WideV1 {
+        let mut hasher = FractWide::new();
+        read_chunks(reader, |chunk| hasher.update(chunk))?;
+        return Ok(FileDigest::Fract512(hasher.finalize().into()));
+    }
+
+    let mut hasher = Fract::new();
+    read_chunks(reader, |chunk| hasher.update(chunk))?;
+
+    let hash = if use_512 {
+        FileDigest::Fract512(hasher.finalize512().into())
+    } else {
+        FileDigest::Fract256(hasher.finalize().into())
+    };
+
+    Ok(hash)
+}
+
+/// Passes everything `reader` yields to `update` through a wiped buffer
+fn read_chunks<R: Read>(mut reader: R, mut update: impl FnMut(&[u8])) -> io::Result<()> {
+    let mut buffer = Zeroizing::new(vec![0u8; READ_BUFFER_SIZE]);
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(n) => update(&buffer[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Streams a reader through the tree hasher in large batches of chunks
+fn tree_hash_reader<R: Read>(mut reader: R, use_512: bool) -> io::Result<FileDigest> {
+    let mut hasher = FractTree::new();
+    let mut buffer = Zeroizing::new(vec![0u8; TREE_READ_BUFFER_SIZE]);
+
+    loop {
+        // Fill the whole buffer so each batch has enough chunks to spread
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match reader.read(&mut buffer[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        if filled == 0 {
+            break;
+        }
+
+        #[cfg(feature = "parallel")]
+        hasher.update_parallel(&buffer[..filled]);
+        #[cfg(not(feature = "parallel"))]
+        hasher.update(&buffer[..filled]);
+    }
+
+    let hash = if use_512 {
+        FileDigest::Fract512(hasher.finalize512().into())
+    } else {
+        FileDigest::Fract256(hasher.finalize().into())
+    };
+
+    Ok(hash)
+}
+
+/// Algorithm selected by the command line options
+fn selected_algorithm(args: &Args) -> fract::Algorithm {
+    args.algorithm.versioned(args.use_512, args.tree)
+}
+
+/// Formats the hash of one input in the selected output format
+fn format_hash(args: &Args, hash: &FileDigest, name: impl std::fmt::Display) -> String {
+    let algorithm = selected_algorithm(args);
+    let mode_char = if args.binary { '*' } else { ' ' };
+
+    if args.tag {
+        format!("{} ({}) = {}", algorithm, name, hash)
+    } else if args.untagged {
+        format!("{} {}{}", hash, mode_char, name)
+    } else {
+        format!("{}:{} {}{}", algorithm, hash, mode_char, name)
+    }
+}
+
+/// Prints the hash of one input in the selected output format
+fn print_hash(args: &Args, hash: &FileDigest, name: impl std::fmt::Display) {
+    println!("{}", format_hash(args, hash, name));
+}
+
+fn hash_stdin(args: &Args) -> io::Result<()> {
+    let stdin = io::stdin();
+    let hash = hash_reader(stdin.lock(), selected_algorithm(args))?;
+    print_hash(args, &hash, "-");
+
+    Ok(())
+}
+
+fn hash_files(files: &[PathBuf], args: &Args) -> io::Result<()> {
+    for file_path in files {
+        if file_path.to_string_lossy() == "-" {
+            hash_stdin(args)?;
+            continue;
+        }
+
+        let file = match File::open(file_path) {
+            Ok(f) => f,
+            Err(e) => {
+                eprintln!("fract: {}: {}", file_path.display(), e);
+                continue;
+            }
+        };
+
+        let hash = match hash_reader(file, selected_algorithm(args)) {
+            Ok(h) => h,
+            Err(e) => {
+                eprintln!("fract: {}: {}", file_path.display(), e);
+                continue;
+            }
+        };
+
+        print_hash(args, &hash, file_path.display());
+    }
+
+    Ok(())
+}
+
+fn check_hashes(check_file: &PathBuf, args: &Args) -> io::Result<()> {
+    let file = File::open(check_file)?;
+    let reader = BufReader::new(file);
+    let mut failed = false;
+    let mut line_num = 0;
+
+    for line in reader.lines() {
+        line_num += 1;
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let entry = match CheckEntry::parse(line, args.algorithm, args.tree) {
+            Ok(entry) => entry,
+            Err(reason) => {
+                if args.warn {
+                    eprintln!("{}:{}: {}", check_file.display(), line_num, reason);
+                }
+                continue;
+            }
+        };
+        let filename = entry.filename;
+        let expected_hash = entry.expected;
+
+        let file_path = PathBuf::from(filename);
+        if !file_path.exists() && filename != "-" {
+            eprintln!("{}: FAILED open or read", filename);
+            failed = true;
+            continue;
+        }
+
+        let file = File::open(&file_path)?;
+        let actual_hash = hash_reader(file, entry.algorithm)?;
+
+        if actual_hash == expected_hash {
+            if args.verbose {
+                println!("{}: OK", filename);
+            }
+        } else {
+            eprintln!("{}: FAILED", filename);
+            if args.verbose {
+                eprintln!("  Expected: {}", expected_hash);
+                eprintln!("  Actual:   {}", actual_hash);
+            }
+            failed = true;
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Reads a 32-byte key from a key file (raw or hex encoded)
+fn read_key_file(path: &Path) -> io::Result<Zeroizing<[u8; FractAead::KEY_LEN]>> {
+    let contents = Zeroizing::new(std::fs::read(path)?);
+    let mut key = Zeroizing::new([0u8; FractAead::KEY_LEN]);
+
+    if contents.len() == FractAead::KEY_LEN {
+        key.copy_from_slice(&contents);
+        return Ok(key);
+    }
+
+    hex::decode_to_slice(contents.trim_ascii(), key.as_mut()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{}: key file must hold 32 raw bytes or 64 hex characters",
+                path.display()
+            ),
+        )
+    })?;
+    Ok(key)
+}
+
+/// Reads the first line of a passphrase file
+fn read_passphrase_file(path: &Path) -> io::Result<Zeroizing<Vec<u8>>> {
+    let contents = Zeroizing::new(std::fs::read(path)?);
+    let line = contents.split(|&b| b == b'\n').next().unwrap_or(&[]);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+    if line.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: passphrase is empty", path.display()),
+        ));
+    }
+    Ok(Zeroizing::new(line.to_vec()))
+}
+
+/// Resolves the stream key for `header` from the command line key options
+fn stream_key(key: &KeyArgs, header: &Header) -> io::Result<Zeroizing<[u8; FractAead::KEY_LEN]>> {
+    match (&key.key_file, &key.passphrase_file, header.key_source) {
+        (Some(path), _, KeySource::KeyFile) => read_key_file(path),
+        (_, Some(path), KeySource::Passphrase(params)) => {
+            let passphrase = read_passphrase_file(path)?;
+            let key = stream::passphrase_key(&passphrase, &header.salt, &params)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            Ok(Zeroizing::new(key))
+        }
+        (_, _, KeySource::KeyFile) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "input was encrypted with a key file; use --key-file",
+        )),
+        (_, _, KeySource::Passphrase(_)) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "input was encrypted with a passphrase; use --passphrase-file",
+        )),
+    }
+}
+
+/// Opens the input file, or stdin when none is given
+fn open_input(input: Option<&Path>) -> io::Result<Box<dyn Read>> {
+    Ok(match input {
+        Some(path) => Box::new(File::open(path)?),
+        None => Box::new(io::stdin().lock()),
+    })
+}
+
+/// Creates the output file, or uses stdout when none is given
+fn create_output(output: Option<&Path>) -> io::Result<Box<dyn Write>> {
+    Ok(match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    })
+}
+
+/// Runs `f` and removes the output file if it fails, so no partial
+/// plaintext or ciphertext is left behind
+fn with_output<F>(output: Option<&Path>, f: F) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: FnOnce(Box<dyn Write>) -> Result<(), Box<dyn std::error::Error>>,
+{
+    let result = create_output(output).map_err(Into::into).and_then(f);
+
+    if let (Err(_), Some(path)) = (&result, output) {
+        let _ = std::fs::remove_file(path);
+    }
+    result
+}
+
+fn encrypt_file(
+    input: Option<&Path>,
+    output: Option<&Path>,
+    key: &KeyArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut header = Header {
+        key_source: if key.key_file.is_some() {
+            KeySource::KeyFile
+        } else {
+            KeySource::Passphrase(password::Params::DEFAULT)
+        },
+        chunk_size: stream::DEFAULT_CHUNK_SIZE,
+        salt: [0; stream::SALT_LEN],
+        nonce_prefix: [0; stream::NONCE_PREFIX_LEN],
+    };
+    if matches!(header.key_source, KeySource::Passphrase(_)) {
+        getrandom::fill(&mut header.salt)?;
+    }
+    getrandom::fill(&mut header.nonce_prefix)?;
+
+    let stream_key = stream_key(key, &header)?;
+    let reader = open_input(input)?;
+
+    with_output(output, |writer| {
+        stream::encrypt(&stream_key, &header, reader, writer)?;
+        Ok(())
+    })
+}
+
+fn decrypt_file(
+    input: Option<&Path>,
+    output: Option<&Path>,
+    key: &KeyArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut reader = open_input(input)?;
+    let header = Header::read_from(&mut reader)?;
+    let stream_key = stream_key(key, &header)?;
+
+    with_output(output, |writer| {
+        stream::decrypt(&stream_key, &header, reader, writer)?;
+        Ok(())
+    })
+}
+
+fn run_kdf(
+    input: Option<&Path>,
+    salt: &str,
+    info: &str,
+    length: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let salt = hex::decode(salt).map_err(|e| format!("invalid --salt hex: {}", e))?;
+
+    let mut ikm = Zeroizing::new(Vec::new());
+    open_input(input)?.read_to_end(&mut ikm)?;
+
+    let okm = Zeroizing::new(fract::kdf::derive(&salt, &ikm, info.as_bytes(), length));
+    println!("{}", hex::encode(&okm));
+
+    Ok(())
+}
+
+/// Reads the first line of the input as a password
+fn read_password(input: Option<&Path>) -> io::Result<Zeroizing<Vec<u8>>> {
+    let mut line = Zeroizing::new(Vec::new());
+    BufReader::new(open_input(input)?).read_until(b'\n', &mut line)?;
+
+    let password = line.strip_suffix(b"\n").unwrap_or(&line);
+    let password = password.strip_suffix(b"\r").unwrap_or(password);
+    Ok(Zeroizing::new(password.to_vec()))
+}
+
+fn hash_password(
+    input: Option<&Path>,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let params = password::Params::new(m_cost, t_cost, p_cost)?;
+    let password = read_password(input)?;
+
+    let mut salt = [0u8; password::RECOMMENDED_SALT_LEN];
+    getrandom::fill(&mut salt)?;
+
+    println!("{}", password::hash_encoded(&password, &salt, &params)?);
+    Ok(())
+}
+
+fn verify_password(input: Option<&Path>, phc: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let password = read_password(input)?;
+
+    password::verify(&password, phc)?;
+    println!("OK");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        use clap::CommandFactory;
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn test_hash_consistency() {
+        let data = b"test data for consistency";
+        let hash1 = hex::encode(Fract::hash(data));
+        let hash2 = hex::encode(Fract::hash(data));
+        assert_eq!(hash1, hash2);
+
+        let hash512_1 = hex::encode(Fract::hash512(data));
+        let hash512_2 = hex::encode(Fract::hash512(data));
+        assert_eq!(hash512_1, hash512_2);
+    }
+
+    #[test]
+    fn test_check_line_formats() {
+        let hex = Digest256::from(Fract::hash(b"x")).to_string();
+
+        let bsd = format!("fract256-tree-v1 (dir/a (1).txt) = {}", hex);
+        let entry = CheckEntry::parse(&bsd, Algorithm::Fract, false).unwrap();
+        assert_eq!(entry.algorithm, fract::Algorithm::Fract256TreeV1);
+        assert_eq!(entry.filename, "dir/a (1).txt");
+
+        let tagged = format!("fract256-v1:{} *a.bin", hex);
+        let entry = CheckEntry::parse(&tagged, Algorithm::Fract, true).unwrap();
+        assert_eq!(entry.algorithm, fract::Algorithm::Fract256V1);
+        assert_eq!(entry.filename, "a.bin");
+
+        let bare = format!("{}  a.txt", hex);
+        let entry = CheckEntry::parse(&bare, Algorithm::Fract, true).unwrap();
+        assert_eq!(entry.algorithm, fract::Algorithm::Fract256TreeV1);
+        assert_eq!(entry.filename, "a.txt");
+        assert_eq!(entry.expected, FileDigest::Fract256(hex.parse().unwrap()));
+
+        let wide = FileDigest::Fract512(FractWide::hash(b"x").into());
+        let bare_wide = format!("{}  a.txt", wide);
+        let entry = CheckEntry::parse(&bare_wide, Algorithm::FractWide, false).unwrap();
+        assert_eq!(entry.algorithm, fract::Algorithm::Fract512WideV1);
+        assert_eq!(entry.expected, wide);
+        assert!(CheckEntry::parse(&bare, Algorithm::FractWide, false).is_err());
+
+        let bsd_512 = format!("fract512-v1 (a) = {}", hex);
+        assert!(CheckEntry::parse(&bsd_512, Algorithm::Fract, false).is_err());
+        let unknown = format!("fract999-v1:{}  a", hex);
+        assert!(CheckEntry::parse(&unknown, Algorithm::Fract, false).is_err());
+        assert!(CheckEntry::parse(&hex, Algorithm::Fract, false).is_err());
+    }
+
+    #[test]
+    fn test_output_round_trips_through_check() {
+        let modes: [&[&str]; 6] = [
+            &[],
+            &["-5"],
+            &["--tree"],
+            &["-5", "--tree"],
+            &["-a", "fract-wide"],
+            &["-a", "fract-wide", "-b"],
+        ];
+        let formats: [&[&str]; 3] = [&[], &["--untagged"], &["--tag"]];
+
+        for mode in modes {
+            for format in formats {
+                let argv = ["fract"].iter().chain(mode).chain(format);
+                let args = Args::try_parse_from(argv).unwrap();
+                let algorithm = selected_algorithm(&args);
+                let hash = hash_reader(&b"round trip"[..], algorithm).unwrap();
+                let line = format_hash(&args, &hash, "dir/a b.txt");
+
+                let entry = CheckEntry::parse(&line, args.algorithm, args.tree).unwrap();
+                assert_eq!(entry.algorithm, algorithm, "{}", line);
+                assert_eq!(entry.expected, hash, "{}", line);
+                assert_eq!(entry.filename, "dir/a b.txt", "{}", line);
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! FRACT-AEAD: authenticated encryption on the FRACT duplex
+//!
+//! Construction, on a [`FractDuplex`] with domain `"FRACT-AEAD v1"`:
+//!
+//! 1. `absorb(key)`, `absorb(nonce)`, `absorb(ad)`
+//! 2. Encrypt the plaintext in 15-byte blocks: each ciphertext block is the
+//!    plaintext XORed with the current rate, and the plaintext is absorbed
+//!    so the rate carries the ciphertext into the next permutation.
+//! 3. Squeeze a 128-bit tag.
+//!
+//! # Nonce misuse
+//!
+//! A nonce must never be reused with the same key. Encrypting two messages
+//! under the same key, nonce and associated data reveals the XOR of their
+//! plaintexts up to and including the first block in which they differ.
+//! Tags stay unforgeable, but confidentiality of that prefix is lost.
+//! Random 16-byte nonces are safe for up to about 2^48 messages per key;
+//! a counter is safe for the whole nonce space.
+
+use crate::{FractDuplex, ct_eq};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Duplex domain string for FRACT-AEAD
+const DOMAIN: &[u8] = b"FRACT-AEAD v1";
+
+/// Error returned when a ciphertext fails authentication
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AeadError;
+
+impl fmt::Display for AeadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "authentication failed")
+    }
+}
+
+impl core::error::Error for AeadError {}
+
+/// FRACT-AEAD authenticated encryption
+pub struct FractAead;
+
+impl FractAead {
+    /// Key length in bytes
+    pub const KEY_LEN: usize = 32;
+
+    /// Nonce length in bytes
+    pub const NONCE_LEN: usize = 16;
+
+    /// Tag length in bytes
+    pub const TAG_LEN: usize = 16;
+
+    /// Encrypts `plaintext` and returns `ciphertext || tag`
+    #[cfg(feature = "alloc")]
+    pub fn encrypt(
+        key: &[u8; Self::KEY_LEN],
+        nonce: &[u8; Self::NONCE_LEN],
+        ad: &[u8],
+        plaintext: &[u8],
+    ) -> Vec<u8> {
+        let mut output = Vec::with_capacity(plaintext.len() + Self::TAG_LEN);
+        output.extend_from_slice(plaintext);
+        let tag = Self::encrypt_in_place_detached(key, nonce, ad, &mut output);
+        output.extend_from_slice(&tag);
+        output
+    }
+
+    /// Decrypts `ciphertext || tag`, returning the plaintext
+    #[cfg(feature = "alloc")]
+    pub fn decrypt(
+        key: &[u8; Self::KEY_LEN],
+        nonce: &[u8; Self::NONCE_LEN],
+        ad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, AeadError> {
+        if ciphertext.len() < Self::TAG_LEN {
+            return Err(AeadError);
+        }
+
+        let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - Self::TAG_LEN);
+        let mut output = ciphertext.to_vec();
+        Self::decrypt_in_place_detached(key, nonce, ad, &mut output, tag.try_into().unwrap())?;
+        Ok(output)
+    }
+
+    /// Encrypts `buffer` in place and returns the tag
+    pub fn encrypt_in_place_detached(
+        key: &[u8; Self::KEY_LEN],
+        nonce: &[u8; Self::NONCE_LEN],
+        ad: &[u8],
+        buffer: &mut [u8],
+    ) -> [u8; Self::TAG_LEN] {
+        let mut duplex = Self::start(key, nonce, ad);
+        duplex.crypt(buffer, false);
+
+        let mut tag = [0u8; Self::TAG_LEN];
+        duplex.squeeze(&mut tag);
+        tag
+    }
+
+    /// Decrypts `buffer` in place after checking `tag`
+    ///
+    /// On failure the buffer is zeroed, so unauthenticated plaintext is
+    /// never released.
+    pub fn decrypt_in_place_detached(
+        key: &[u8; Self::KEY_LEN],
+        nonce: &[u8; Self::NONCE_LEN],
+        ad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8; Self::TAG_LEN],
+    ) -> Result<(), AeadError> {
+        let mut duplex = Self::start(key, nonce, ad);
+        duplex.crypt(buffer, true);
+
+        let mut expected = [0u8; Self::TAG_LEN];
+        duplex.squeeze(&mut expected);
+
+        if ct_eq(&expected, tag) {
+            Ok(())
+        } else {
+            buffer.fill(0);
+            Err(AeadError)
+        }
+    }
+
+    /// Absorbs key, nonce and associated data into a fresh duplex
+    fn start(key: &[u8; Self::KEY_LEN], nonce: &[u8; Self::NONCE_LEN], ad: &[u8]) -> FractDuplex {
+        let mut duplex = FractDuplex::new(DOMAIN);
+        duplex.absorb(key);
+        duplex.absorb(nonce);
+        duplex.absorb(ad);
+        duplex
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [0x42; 32];
+    const NONCE: [u8; 16] = [0x24; 16];
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_vectors() {
+        let vectors: [(&[u8], &[u8], &str); 3] = [
+            (b"", b"", "0c15041ad7f2fffcd6cde984819d301b"),
+            (
+                b"header",
+                b"hello cat",
+                "345eac8e9c924cc76b866c34ad238e428ccc0c611cf8f97057",
+            ),
+            (
+                b"",
+                &[0x61; 40],
+                "4067fc022aae11a4c3db4fd9300013cee3f55b2de6cbd9f9bfee2d44fba08bbfb73871c0d63306082f78f9070a4103f4a3de8c02ade9d617",
+            ),
+        ];
+
+        for (ad, plaintext, expected) in vectors {
+            let ciphertext = FractAead::encrypt(&KEY, &NONCE, ad, plaintext);
+            assert_eq!(hex::encode(&ciphertext), expected);
+            assert_eq!(
+                FractAead::decrypt(&KEY, &NONCE, ad, &ciphertext).unwrap(),
+                plaintext
+            );
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_roundtrip_lengths() {
+        for len in [0, 1, 14, 15, 16, 30, 31, 100, 1000] {
+            let plaintext: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let ciphertext = FractAead::encrypt(&KEY, &NONCE, b"ad", &plaintext);
+            assert_eq!(ciphertext.len(), len + FractAead::TAG_LEN);
+            assert_eq!(
+                FractAead::decrypt(&KEY, &NONCE, b"ad", &ciphertext).unwrap(),
+                plaintext
+            );
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_tampering_is_rejected() {
+        let ciphertext = FractAead::encrypt(&KEY, &NONCE, b"ad", b"attack at dawn");
+
+        for i in 0..ciphertext.len() {
+            let mut tampered = ciphertext.clone();
+            tampered[i] ^= 1;
+            assert_eq!(
+                FractAead::decrypt(&KEY, &NONCE, b"ad", &tampered),
+                Err(AeadError)
+            );
+        }
+
+        let mut other_nonce = NONCE;
+        other_nonce[0] ^= 1;
+        assert!(FractAead::decrypt(&KEY, &other_nonce, b"ad", &ciphertext).is_err());
+        assert!(FractAead::decrypt(&KEY, &NONCE, b"da", &ciphertext).is_err());
+        assert!(FractAead::decrypt(&KEY, &NONCE, b"ad", &ciphertext[..15]).is_err());
+    }
+
+    #[test]
+    fn test_in_place_detached() {
+        let mut buffer = *b"in-place message";
+        let tag = FractAead::encrypt_in_place_detached(&KEY, &NONCE, b"", &mut buffer);
+        assert_ne!(&buffer, b"in-place message");
+
+        let mut tampered = buffer;
+        tampered[0] ^= 1;
+        assert!(
+            FractAead::decrypt_in_place_detached(&KEY, &NONCE, b"", &mut tampered, &tag).is_err()
+        );
+        assert_eq!(tampered, [0u8; 16]);
+
+        FractAead::decrypt_in_place_detached(&KEY, &NONCE, b"", &mut buffer, &tag).unwrap();
+        assert_eq!(&buffer, b"in-place message");
+    }
+}
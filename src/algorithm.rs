@@ -0,0 +1,195 @@
+//! Versioned algorithm identifiers
+//!
+//! A bare hex digest does not say which function produced it. Each
+//! [`Algorithm`] names one frozen output definition, such as `fract256-v1`,
+//! so stored digests stay verifiable: a later change to the map, the
+//! padding or the IV would ship under a new identifier instead of silently
+//! changing what `fract256-v1` means. The outputs of every identifier are
+//! pinned by the known-answer file `tests/data/kat-v1.txt`.
+//!
+//! Tagged digests are written `<id>:<hex>`, e.g. `fract256-v1:6e4371...`.
+
+use crate::{Fract, FractParams, FractTree, FractWide, HashError};
+use core::fmt;
+use core::str::FromStr;
+
+/// A versioned FRACT output definition
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// FRACT-256 v1, 32-byte digest (`fract256-v1`)
+    Fract256V1,
+    /// FRACT-512 v1, 64-byte digest (`fract512-v1`)
+    Fract512V1,
+    /// FRACT-Tree v1 with a 32-byte root (`fract256-tree-v1`)
+    Fract256TreeV1,
+    /// FRACT-Tree v1 with a 64-byte root (`fract512-tree-v1`)
+    Fract512TreeV1,
+    /// FRACT-Wide v1, 64-byte digest with a 384-bit capacity (`fract512-wide-v1`)
+    Fract512WideV1,
+}
+
+/// Error returned for an unknown algorithm identifier
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseAlgorithmError;
+
+impl fmt::Display for ParseAlgorithmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown algorithm identifier")
+    }
+}
+
+impl core::error::Error for ParseAlgorithmError {}
+
+impl Algorithm {
+    /// Every identifier, in a fixed order
+    pub const ALL: [Algorithm; 5] = [
+        Algorithm::Fract256V1,
+        Algorithm::Fract512V1,
+        Algorithm::Fract256TreeV1,
+        Algorithm::Fract512TreeV1,
+        Algorithm::Fract512WideV1,
+    ];
+
+    /// The identifier string, e.g. `fract256-v1`
+    pub const fn id(&self) -> &'static str {
+        match self {
+            Algorithm::Fract256V1 => "fract256-v1",
+            Algorithm::Fract512V1 => "fract512-v1",
+            Algorithm::Fract256TreeV1 => "fract256-tree-v1",
+            Algorithm::Fract512TreeV1 => "fract512-tree-v1",
+            Algorithm::Fract512WideV1 => "fract512-wide-v1",
+        }
+    }
+
+    /// Digest length in bytes
+    pub const fn output_len(&self) -> usize {
+        match self {
+            Algorithm::Fract256V1 | Algorithm::Fract256TreeV1 => 32,
+            Algorithm::Fract512V1 | Algorithm::Fract512TreeV1 | Algorithm::Fract512WideV1 => 64,
+        }
+    }
+
+    /// Whether this is a FRACT-Tree mode
+    pub const fn is_tree(&self) -> bool {
+        matches!(self, Algorithm::Fract256TreeV1 | Algorithm::Fract512TreeV1)
+    }
+
+    /// Hashes `data` in one shot into `out`
+    ///
+    /// `out` must be exactly [`Algorithm::output_len`] bytes long.
+    pub fn hash_into(&self, data: &[u8], out: &mut [u8]) -> Result<(), HashError> {
+        if out.len() != self.output_len() {
+            return Err(HashError::OutputLength);
+        }
+
+        match self {
+            Algorithm::Fract256V1 => out.copy_from_slice(&Fract::hash(data)),
+            Algorithm::Fract512V1 => out.copy_from_slice(&Fract::hash512(data)),
+            Algorithm::Fract256TreeV1 => out.copy_from_slice(&FractTree::hash(data)),
+            Algorithm::Fract512TreeV1 => {
+                let mut tree = FractTree::new();
+                tree.update(data);
+                out.copy_from_slice(&tree.finalize512());
+            }
+            Algorithm::Fract512WideV1 => out.copy_from_slice(&FractWide::hash(data)),
+        }
+        Ok(())
+    }
+
+    /// Splits a tagged digest `<id>:<hex>` into its algorithm and hex part
+    ///
+    /// The hex part is returned unchecked; parse it with
+    /// [`Digest256`](crate::Digest256) or [`Digest512`](crate::Digest512).
+    pub fn split_tagged(tagged: &str) -> Result<(Algorithm, &str), ParseAlgorithmError> {
+        let (id, hex) = tagged.split_once(':').ok_or(ParseAlgorithmError)?;
+        Ok((id.parse()?, hex))
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = ParseAlgorithmError;
+
+    /// Parses an identifier; matching is exact and case-sensitive
+    fn from_str(s: &str) -> Result<Self, ParseAlgorithmError> {
+        Self::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.id() == s)
+            .ok_or(ParseAlgorithmError)
+    }
+}
+
+impl FractParams {
+    /// The identifier of the frozen profile these parameters match, if any
+    pub fn algorithm(&self) -> Option<Algorithm> {
+        if *self == Self::FRACT_256_V1 {
+            Some(Algorithm::Fract256V1)
+        } else if *self == Self::FRACT_512_V1 {
+            Some(Algorithm::Fract512V1)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_roundtrip() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(algorithm.id().parse(), Ok(algorithm));
+            assert_eq!(algorithm.to_string(), algorithm.id());
+        }
+        assert_eq!("FRACT256-V1".parse::<Algorithm>(), Err(ParseAlgorithmError));
+        assert_eq!("fract256".parse::<Algorithm>(), Err(ParseAlgorithmError));
+    }
+
+    #[test]
+    fn test_hash_into() {
+        let mut out = [0u8; 32];
+        Algorithm::Fract256V1.hash_into(b"abc", &mut out).unwrap();
+        assert_eq!(out, Fract::hash(b"abc"));
+
+        let mut wide = [0u8; 64];
+        Algorithm::Fract512TreeV1
+            .hash_into(b"abc", &mut wide)
+            .unwrap();
+        assert_ne!(wide, Fract::hash512(b"abc"));
+        assert_eq!(
+            Algorithm::Fract512V1.hash_into(b"abc", &mut out),
+            Err(HashError::OutputLength)
+        );
+    }
+
+    #[test]
+    fn test_split_tagged() {
+        let tagged = "fract512-v1:00ff";
+        assert_eq!(
+            Algorithm::split_tagged(tagged),
+            Ok((Algorithm::Fract512V1, "00ff"))
+        );
+        assert!(Algorithm::split_tagged("00ff").is_err());
+        assert!(Algorithm::split_tagged("sha256:00ff").is_err());
+    }
+
+    #[test]
+    fn test_params_algorithm() {
+        assert_eq!(
+            FractParams::FRACT_256_V1.algorithm(),
+            Some(Algorithm::Fract256V1)
+        );
+        assert_eq!(
+            FractParams::FRACT_512_V1.algorithm(),
+            Some(Algorithm::Fract512V1)
+        );
+        let hardened = FractParams::builder().rounds(16).build().unwrap();
+        assert_eq!(hardened.algorithm(), None);
+    }
+}
@@ -0,0 +1,212 @@
+//! FRACT duplex sponge for stateful protocols
+//!
+//! Every operation is split into blocks of at most 15 data bytes. Each block
+//! is padded with a single `0x01` byte after its data, and a frame byte is
+//! XORed into the top byte of the capacity before the permutation. The
+//! frame byte names the operation and flags the last block of each call,
+//! so any sequence of absorb, squeeze and ratchet calls is unambiguous:
+//! `absorb(b"ab"); absorb(b"c")` and `absorb(b"abc")` lead to different
+//! states, as do `squeeze(32)` and two `squeeze(16)` calls.
+
+use crate::{Fract, RATE};
+
+/// Data bytes per duplex block (one rate byte is reserved for padding)
+const DUPLEX_RATE: usize = RATE - 1;
+
+/// Frame byte for absorbed data blocks
+const FRAME_ABSORB: u8 = 0x10;
+
+/// Frame byte for squeezed output blocks
+const FRAME_SQUEEZE: u8 = 0x20;
+
+/// Frame byte for ratchet blocks
+const FRAME_RATCHET: u8 = 0x30;
+
+/// Frame byte for encrypted blocks
+const FRAME_CRYPT: u8 = 0x40;
+
+/// Flag marking the last block of an operation
+const FRAME_LAST: u8 = 0x01;
+
+/// FRACT duplex sponge
+#[derive(Clone, Debug)]
+pub struct FractDuplex {
+    /// Sponge whose state and permutation carry the duplex
+    pub(crate) sponge: Fract,
+}
+
+impl FractDuplex {
+    /// Creates a duplex whose initial state is derived from `domain`
+    ///
+    /// Different protocols should use different domain strings; the state
+    /// is derived with [`Fract::with_customization`].
+    pub fn new(domain: &[u8]) -> Self {
+        Self {
+            sponge: Fract::with_customization(b"FRACT-Duplex", domain),
+        }
+    }
+
+    /// Absorbs `data` as one framed operation
+    pub fn absorb(&mut self, data: &[u8]) {
+        // Empty input still absorbs one (padding-only) block
+        let blocks = data.len().div_ceil(DUPLEX_RATE).max(1);
+
+        for i in 0..blocks {
+            let start = i * DUPLEX_RATE;
+            let end = (start + DUPLEX_RATE).min(data.len());
+            self.absorb_block(&data[start..end], FRAME_ABSORB, i + 1 == blocks);
+        }
+    }
+
+    /// Fills `out` with output as one framed operation
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        // Empty output still runs one block to keep the framing
+        let blocks = out.len().div_ceil(RATE).max(1);
+
+        for i in 0..blocks {
+            self.absorb_block(&[], FRAME_SQUEEZE, i + 1 == blocks);
+
+            let start = i * RATE;
+            let end = (start + RATE).min(out.len());
+            out[start..end].copy_from_slice(&self.sponge.rate_bytes()[..end - start]);
+        }
+    }
+
+    /// Irreversibly forgets the rate for forward secrecy
+    ///
+    /// After a ratchet, recovering any earlier state requires guessing the
+    /// 128 bits of rate that were overwritten with zeros.
+    pub fn ratchet(&mut self) {
+        self.absorb_block(&[], FRAME_RATCHET, true);
+        self.sponge.state[0] = 0;
+        self.sponge.state[1] = 0;
+    }
+
+    /// Encrypts or decrypts `buf` in place as one framed operation
+    ///
+    /// The keystream is the current rate; the plaintext is absorbed so the
+    /// rate holds the ciphertext before the block is permuted.
+    pub(crate) fn crypt(&mut self, buf: &mut [u8], decrypt: bool) {
+        let blocks = buf.len().div_ceil(DUPLEX_RATE).max(1);
+
+        for i in 0..blocks {
+            let start = i * DUPLEX_RATE;
+            let end = (start + DUPLEX_RATE).min(buf.len());
+            let chunk = &mut buf[start..end];
+            let keystream = self.sponge.rate_bytes();
+
+            let mut plaintext = [0u8; DUPLEX_RATE];
+            if decrypt {
+                for (byte, key) in chunk.iter_mut().zip(keystream) {
+                    *byte ^= key;
+                }
+                plaintext[..chunk.len()].copy_from_slice(chunk);
+            } else {
+                plaintext[..chunk.len()].copy_from_slice(chunk);
+                for (byte, key) in chunk.iter_mut().zip(keystream) {
+                    *byte ^= key;
+                }
+            }
+
+            self.absorb_block(&plaintext[..chunk.len()], FRAME_CRYPT, i + 1 == blocks);
+        }
+    }
+
+    /// Absorbs one padded block of at most `DUPLEX_RATE` bytes under `frame`
+    fn absorb_block(&mut self, data: &[u8], frame: u8, last: bool) {
+        let frame = if last { frame | FRAME_LAST } else { frame };
+
+        self.sponge.buffer = [0; RATE];
+        self.sponge.buffer[..data.len()].copy_from_slice(data);
+        self.sponge.buffer[data.len()] = 0x01;
+        self.sponge.state[3] ^= (frame as u64) << 56;
+        self.sponge.absorb_block();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn squeeze32(duplex: &mut FractDuplex) -> [u8; 32] {
+        let mut output = [0u8; 32];
+        duplex.squeeze(&mut output);
+        output
+    }
+
+    #[test]
+    fn test_vectors() {
+        let mut duplex = FractDuplex::new(b"test");
+        duplex.absorb(b"hello cat");
+        assert_eq!(
+            hex::encode(squeeze32(&mut duplex)),
+            "d12e557d7ed505239ccfca0c17e0e53a7e74fbdb4348239463b5398de71ea0bf"
+        );
+
+        duplex.absorb(&[0x61; 40]);
+        duplex.ratchet();
+        assert_eq!(
+            hex::encode(squeeze32(&mut duplex)),
+            "d49b7f0e28a291978279f4f6cab2eff9bc4831de8862b8dce8f0c3659d248854"
+        );
+
+        duplex.absorb(b"");
+        let mut short = [0u8; 5];
+        duplex.squeeze(&mut short);
+        assert_eq!(hex::encode(short), "1d2b25657e");
+    }
+
+    #[test]
+    fn test_absorb_calls_are_framed() {
+        let mut split = FractDuplex::new(b"test");
+        split.absorb(b"ab");
+        split.absorb(b"c");
+
+        let mut joined = FractDuplex::new(b"test");
+        joined.absorb(b"abc");
+
+        assert_ne!(squeeze32(&mut split), squeeze32(&mut joined));
+
+        let mut padded = FractDuplex::new(b"test");
+        padded.absorb(b"abc\x01");
+        assert_ne!(
+            squeeze32(&mut padded),
+            squeeze32(&mut FractDuplex::new(b"test"))
+        );
+    }
+
+    #[test]
+    fn test_squeeze_calls_are_framed() {
+        let mut whole = FractDuplex::new(b"test");
+        let whole = squeeze32(&mut whole);
+
+        let mut split = FractDuplex::new(b"test");
+        let mut first = [0u8; 16];
+        let mut second = [0u8; 16];
+        split.squeeze(&mut first);
+        split.squeeze(&mut second);
+
+        assert_ne!(whole[..16], first[..]);
+        assert_ne!(whole[16..], second[..]);
+    }
+
+    #[test]
+    fn test_ratchet_changes_output() {
+        let mut plain = FractDuplex::new(b"test");
+        plain.absorb(b"key");
+
+        let mut ratcheted = plain.clone();
+        ratcheted.ratchet();
+        assert_eq!(ratcheted.sponge.state[..2], [0, 0]);
+
+        assert_ne!(squeeze32(&mut plain), squeeze32(&mut ratcheted));
+    }
+
+    #[test]
+    fn test_domains_differ() {
+        assert_ne!(
+            squeeze32(&mut FractDuplex::new(b"a")),
+            squeeze32(&mut FractDuplex::new(b"b"))
+        );
+    }
+}
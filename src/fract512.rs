@@ -0,0 +1,50 @@
+//! FRACT-512: the 512-bit output variant as its own hasher type
+//!
+//! [`Fract512`] absorbs exactly like [`Fract`] and finalizes with
+//! [`Fract::finalize512`]. Having a separate type gives the 512-bit
+//! variant its own fixed output size, e.g. for the `digest` traits.
+
+use crate::Fract;
+
+/// FRACT-512 hasher
+#[derive(Clone, Debug, Default)]
+pub struct Fract512 {
+    /// Underlying sponge
+    pub(crate) inner: Fract,
+}
+
+impl Fract512 {
+    /// Creates a new FRACT-512 hasher
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Absorbs data into the state
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Finalizes and returns the 512-bit hash
+    pub fn finalize(self) -> [u8; 64] {
+        self.inner.finalize512()
+    }
+
+    /// Convenience method: hash data in one shot
+    pub fn hash(data: &[u8]) -> [u8; 64] {
+        Fract::hash512(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_hash512() {
+        let mut hasher = Fract512::new();
+        hasher.update(b"hello ");
+        hasher.update(b"world");
+        assert_eq!(hasher.finalize(), Fract::hash512(b"hello world"));
+        assert_eq!(Fract512::hash(b""), Fract::hash512(b""));
+    }
+}
@@ -0,0 +1,195 @@
+//! `core::hash` integration: keyed FRACT for `HashMap` keys
+//!
+//! [`FractHasher`] computes the first 64 bits of FRACT-256 customized with
+//! `("FRACT-Hasher", le64(k0) || le64(k1))` over the written bytes. The
+//! keyed initial state is derived once per [`RandomFractState`], so each
+//! hasher starts from a copy of it. Writes shorter than the free space in
+//! the 16-byte rate buffer are copied without touching the sponge, and
+//! `finish` pads and runs a single permutation on a copy of the state.
+//!
+//! [`RandomFractState::new`] derives its keys from a new `std` `RandomState`.
+//! Like std's, they come from a per-thread random seed read from the
+//! operating system once, and are distinct for every map.
+
+use crate::{Fract, RATE};
+use core::hash::{BuildHasher, Hasher};
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
+
+/// Keyed FRACT hasher for hash tables
+#[derive(Clone, Debug)]
+pub struct FractHasher {
+    /// Sponge with the keyed prefix already absorbed
+    pub(crate) sponge: Fract,
+}
+
+impl FractHasher {
+    /// Creates a hasher keyed with `(k0, k1)`
+    ///
+    /// Derives the keyed state on every call; build hashers through
+    /// [`RandomFractState`] to derive it only once per map.
+    pub fn new_with_keys(k0: u64, k1: u64) -> Self {
+        RandomFractState::with_keys(k0, k1).build_hasher()
+    }
+}
+
+impl Hasher for FractHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let len = self.sponge.buffer_len;
+
+        // Fast path: the bytes fit in the rate buffer without completing it
+        if bytes.len() < RATE - len {
+            self.sponge.buffer[len..len + bytes.len()].copy_from_slice(bytes);
+            self.sponge.buffer_len += bytes.len();
+            self.sponge.total_len += bytes.len() as u64;
+        } else {
+            self.sponge.update(bytes);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut sponge = self.sponge.clone();
+        sponge.pad_and_absorb(sponge.domain);
+        sponge.state[0]
+    }
+}
+
+/// `BuildHasher` producing [`FractHasher`]s under random per-map keys
+#[derive(Clone, Debug)]
+pub struct RandomFractState {
+    /// Keyed initial sponge shared by all hashers of this state
+    pub(crate) sponge: Fract,
+}
+
+impl RandomFractState {
+    /// Creates a state with keys derived from std's per-thread random seed,
+    /// distinct per map
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        let random = RandomState::new();
+        Self::with_keys(random.hash_one(0u64), random.hash_one(1u64))
+    }
+
+    /// Creates a state with fixed keys, e.g. for reproducible tests
+    pub fn with_keys(k0: u64, k1: u64) -> Self {
+        let mut key = [0u8; 16];
+        key[..8].copy_from_slice(&k0.to_le_bytes());
+        key[8..].copy_from_slice(&k1.to_le_bytes());
+
+        Self {
+            sponge: Fract::with_customization(b"FRACT-Hasher", &key),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for RandomFractState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildHasher for RandomFractState {
+    type Hasher = FractHasher;
+
+    fn build_hasher(&self) -> FractHasher {
+        FractHasher {
+            sponge: self.sponge.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_customized_fract() {
+        let mut key = [0u8; 16];
+        key[..8].copy_from_slice(&1u64.to_le_bytes());
+        key[8..].copy_from_slice(&2u64.to_le_bytes());
+
+        for len in [0, 1, 15, 16, 17, 100] {
+            let data = vec![0x61u8; len];
+            let mut hasher = FractHasher::new_with_keys(1, 2);
+            hasher.write(&data);
+
+            let mut expected = Fract::with_customization(b"FRACT-Hasher", &key);
+            expected.update(&data);
+            let expected = expected.finalize();
+            assert_eq!(
+                hasher.finish(),
+                u64::from_le_bytes(expected[..8].try_into().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn test_trailing_byte_changes_hash() {
+        // Keys such as b"abcdefghijk" and b"abcdefghijk\x01" must not collide
+        let state = RandomFractState::with_keys(1, 2);
+        for len in 0..48 {
+            let data = vec![0x61u8; len];
+            let mut extended = data.clone();
+            extended.push(0x01);
+            assert_ne!(
+                state.hash_one(&data[..]),
+                state.hash_one(&extended[..]),
+                "length {}",
+                len
+            );
+
+            let mut a = state.build_hasher();
+            let mut b = state.build_hasher();
+            a.write(&data);
+            b.write(&extended);
+            assert_ne!(a.finish(), b.finish(), "length {}", len);
+        }
+    }
+
+    #[test]
+    fn test_split_writes_match() {
+        let state = RandomFractState::with_keys(7, 8);
+        let data: Vec<u8> = (0..50).collect();
+
+        let mut whole = state.build_hasher();
+        whole.write(&data);
+
+        let mut split = state.build_hasher();
+        for piece in [&data[..3], &data[3..4], &data[4..20], &data[20..]] {
+            split.write(piece);
+        }
+
+        assert_eq!(split.finish(), whole.finish());
+        // finish does not consume the hasher
+        assert_eq!(split.finish(), whole.finish());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_keys_change_output() {
+        let hash = |state: &RandomFractState| state.hash_one("key");
+
+        let a = RandomFractState::with_keys(0, 0);
+        assert_eq!(hash(&a), hash(&a.clone()));
+        assert_ne!(hash(&a), hash(&RandomFractState::with_keys(0, 1)));
+        assert_ne!(
+            hash(&RandomFractState::new()),
+            hash(&RandomFractState::new())
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_hash_map() {
+        use std::collections::HashMap;
+
+        let mut map: HashMap<String, usize, RandomFractState> = HashMap::default();
+        for i in 0..1000 {
+            map.insert(format!("key {}", i), i);
+        }
+        assert_eq!(map.len(), 1000);
+        assert_eq!(map["key 123"], 123);
+    }
+}
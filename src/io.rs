@@ -0,0 +1,241 @@
+//! `std::io` integration: hashing sinks and pass-through adapters
+//!
+//! [`Fract`] implements [`Write`], so `io::copy(&mut reader, &mut hasher)`
+//! hashes a stream. [`HashingReader`] and [`HashingWriter`] wrap another
+//! reader or writer and hash exactly the bytes that pass through, so a
+//! digest can be computed while data is uploaded, decompressed or copied.
+
+use crate::{Fract, FractWide};
+use std::io::{self, Read, Write};
+
+impl Write for Fract {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.update(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Write for FractWide {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.update(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Fract {
+    /// Convenience method: hash everything `reader` yields (256-bit output)
+    pub fn hash_reader(reader: impl Read) -> io::Result<[u8; 32]> {
+        let mut reader = reader;
+        let mut hasher = Self::new();
+        io::copy(&mut reader, &mut hasher)?;
+        Ok(hasher.finalize())
+    }
+}
+
+/// Reader adapter that hashes the bytes read through it
+#[derive(Clone, Debug)]
+pub struct HashingReader<R> {
+    /// Wrapped reader
+    inner: R,
+    /// Hasher fed with every byte returned to the caller
+    hasher: Fract,
+}
+
+impl<R: Read> HashingReader<R> {
+    /// Wraps `inner` with a plain FRACT-256 hasher
+    pub fn new(inner: R) -> Self {
+        Self::with_hasher(inner, Fract::new())
+    }
+
+    /// Wraps `inner` with a given (e.g. customized) hasher
+    pub fn with_hasher(inner: R, hasher: Fract) -> Self {
+        Self { inner, hasher }
+    }
+
+    /// Returns a reference to the wrapped reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped reader
+    ///
+    /// Bytes read directly from it bypass the hasher.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the hasher holding everything read so far
+    pub fn hasher(&self) -> &Fract {
+        &self.hasher
+    }
+
+    /// Unwraps into the reader and the hasher
+    pub fn into_parts(self) -> (R, Fract) {
+        (self.inner, self.hasher)
+    }
+
+    /// Finalizes the hash of everything read so far
+    pub fn finalize(self) -> [u8; 32] {
+        self.hasher.finalize()
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Writer adapter that hashes the bytes written through it
+#[derive(Clone, Debug)]
+pub struct HashingWriter<W> {
+    /// Wrapped writer
+    inner: W,
+    /// Hasher fed with every byte the wrapped writer accepted
+    hasher: Fract,
+}
+
+impl<W: Write> HashingWriter<W> {
+    /// Wraps `inner` with a plain FRACT-256 hasher
+    pub fn new(inner: W) -> Self {
+        Self::with_hasher(inner, Fract::new())
+    }
+
+    /// Wraps `inner` with a given (e.g. customized) hasher
+    pub fn with_hasher(inner: W, hasher: Fract) -> Self {
+        Self { inner, hasher }
+    }
+
+    /// Returns a reference to the wrapped writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped writer
+    ///
+    /// Bytes written directly to it bypass the hasher.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns the hasher holding everything written so far
+    pub fn hasher(&self) -> &Fract {
+        &self.hasher
+    }
+
+    /// Unwraps into the writer and the hasher
+    pub fn into_parts(self) -> (W, Fract) {
+        (self.inner, self.hasher)
+    }
+
+    /// Finalizes the hash of everything written so far
+    ///
+    /// Does not flush the wrapped writer.
+    pub fn finalize(self) -> [u8; 32] {
+        self.hasher.finalize()
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Only hash what the wrapped writer accepted
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writer accepting at most three bytes per call
+    struct Trickle(Vec<u8>);
+
+    impl Write for Trickle {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n = buf.len().min(3);
+            self.0.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn data() -> Vec<u8> {
+        (0..10_000).map(|i| (i % 253) as u8).collect()
+    }
+
+    #[test]
+    fn test_write_impl() {
+        let data = data();
+        let mut hasher = Fract::new();
+        io::copy(&mut data.as_slice(), &mut hasher).unwrap();
+        assert_eq!(hasher.finalize(), Fract::hash(&data));
+
+        assert_eq!(
+            Fract::hash_reader(data.as_slice()).unwrap(),
+            Fract::hash(&data)
+        );
+    }
+
+    #[test]
+    fn test_hashing_reader() {
+        let data = data();
+        let mut reader = HashingReader::new(data.as_slice());
+        let mut copy = Vec::new();
+        reader.read_to_end(&mut copy).unwrap();
+
+        assert_eq!(copy, data);
+        assert_eq!(reader.finalize(), Fract::hash(&data));
+    }
+
+    #[test]
+    fn test_hashing_writer_hashes_accepted_bytes() {
+        let data = data();
+        let mut writer = HashingWriter::new(Trickle(Vec::new()));
+        assert_eq!(writer.write(&data[..10]).unwrap(), 3);
+        writer.write_all(&data[3..]).unwrap();
+
+        let (sink, hasher) = writer.into_parts();
+        assert_eq!(sink.0, data);
+        assert_eq!(hasher.finalize(), Fract::hash(&data));
+    }
+
+    #[test]
+    fn test_with_hasher() {
+        let hasher = Fract::with_customization(b"", b"upload");
+        let mut writer = HashingWriter::with_hasher(Vec::new(), hasher.clone());
+        writer.write_all(b"payload").unwrap();
+
+        let mut expected = hasher;
+        expected.update(b"payload");
+        assert_eq!(writer.finalize(), expected.finalize());
+    }
+}
@@ -0,0 +1,134 @@
+//! HKDF-style key derivation on FRACT-MAC
+//!
+//! - `extract(salt, ikm)` is the FRACT-MAC tag of `ikm` keyed with `salt`;
+//!   an empty salt is replaced by 32 zero bytes, as in HKDF.
+//! - `expand(prk, info, len)` reads `len` bytes from the keyed XOF of
+//!   `info` under `prk` ([`FractMac::finalize_xof`]), which carries its own
+//!   padding domain, so expanded output never coincides with a MAC tag.
+//!
+//! Unlike HKDF there is no upper bound on the output length. As with HKDF,
+//! a shorter output is a prefix of a longer one for the same inputs.
+
+use crate::FractMac;
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+/// Pseudorandom key length in bytes
+pub const PRK_LEN: usize = 32;
+
+/// Extracts a pseudorandom key from input keying material
+pub fn extract(salt: &[u8], ikm: &[u8]) -> [u8; PRK_LEN] {
+    let salt = if salt.is_empty() {
+        &[0u8; PRK_LEN][..]
+    } else {
+        salt
+    };
+    FractMac::mac(salt, ikm)
+}
+
+/// Expands a pseudorandom key into `out.len()` bytes of output keying material
+pub fn expand_into(prk: &[u8], info: &[u8], out: &mut [u8]) {
+    let mut mac = FractMac::new(prk);
+    mac.update(info);
+    mac.finalize_xof().squeeze(out);
+}
+
+/// Expands a pseudorandom key into `len` bytes of output keying material
+#[cfg(feature = "alloc")]
+pub fn expand(prk: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+    let mut out = vec![0u8; len];
+    expand_into(prk, info, &mut out);
+    out
+}
+
+/// Extract-then-expand in one call
+#[cfg(feature = "alloc")]
+pub fn derive(salt: &[u8], ikm: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+    expand(&extract(salt, ikm), info, len)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors() {
+        let ikm = [0x0b; 22];
+        let salt: Vec<u8> = (0x00..=0x0c).collect();
+        let info: Vec<u8> = (0xf0..=0xf9).collect();
+
+        let prk = extract(&salt, &ikm);
+        assert_eq!(
+            hex::encode(prk),
+            "5db3288651564dcb01eda1f67308229b75e86864c02d35369714542187033586"
+        );
+        assert_eq!(
+            hex::encode(expand(&prk, &info, 42)),
+            "7664b791a0b0fe139325c48fe9a32613fc9bc51b273e710a7a5860344965da598705861da8e71bf43c33"
+        );
+    }
+
+    #[test]
+    fn test_vectors_empty_salt_and_info() {
+        let prk = extract(b"", &[0x0b; 22]);
+        assert_eq!(prk, extract(&[0u8; PRK_LEN], &[0x0b; 22]));
+        assert_eq!(
+            hex::encode(prk),
+            "9509071b7204342cf563e962077e1d21704b30f69a93bacdeebfc914a50bbf99"
+        );
+        assert_eq!(
+            hex::encode(expand(&prk, b"", 42)),
+            "d6e41e2478e626a648470ed521e43716dc58620b440101fc5759b3fd6b7f28bdc104b4834650d436d17e"
+        );
+    }
+
+    #[test]
+    fn test_vectors_long_inputs() {
+        let ikm: Vec<u8> = (0x00..=0x4f).collect();
+        let salt: Vec<u8> = (0x60..=0xaf).collect();
+        let info: Vec<u8> = (0xb0..=0xff).collect();
+
+        let okm = derive(&salt, &ikm, &info, 82);
+        assert_eq!(
+            hex::encode(okm),
+            "23351f60b790efc79f3de6fc7f7130ddae7a7e273f461e1327103baab4905b3504c23486df517adc4870b1392bd6cc58d004309b50e0422a7d4aa7dcad9f2dd67d70be13d6b1925b0eb6fe33b91338c84ae4"
+        );
+    }
+
+    #[test]
+    fn test_extract_distinguishes_trailing_byte() {
+        // ikm and ikm || 0x01 must give different pseudorandom keys
+        for len in 0..48 {
+            let ikm = vec![0x0b; len];
+            let mut extended = ikm.clone();
+            extended.push(0x01);
+            assert_ne!(
+                extract(b"salt", &ikm),
+                extract(b"salt", &extended),
+                "length {}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_long_output() {
+        let prk = extract(b"salt", b"ikm");
+        let long = expand(&prk, b"info", 10_000);
+        let short = expand(&prk, b"info", 100);
+
+        assert_eq!(long[..100], short[..]);
+        assert_ne!(long[..32], long[9_968..]);
+        assert_eq!(
+            hex::encode(&long[9_984..]),
+            "e472722cdb28bb394a8ebaccf7e2d7b7"
+        );
+    }
+
+    #[test]
+    fn test_expand_differs_from_extract() {
+        let prk = extract(b"salt", b"ikm");
+        assert_ne!(expand(&prk, b"", 32), FractMac::mac(&prk, b""));
+        assert_ne!(expand(&prk, b"a", 32), expand(&prk, b"b", 32));
+    }
+}
@@ -3,13 +3,94 @@
 //! This implementation follows the specification in the whitepaper for FRACT,
 //! a cryptographic hash function that leverages hyperchaotic dynamical systems
 //! on finite modular lattices.
+//!
+//! The crate is `no_std` without its default `std` feature. The `alloc`
+//! feature enables the APIs that return `Vec`s (one-shot AEAD, `kdf::expand`,
+//! `merkle`), and `hex` enables the hex convenience functions. The `std`
+//! feature adds the I/O adapters, `stream`, `password` and random hasher keys.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod aead;
+mod algorithm;
+mod duplex;
+mod fract512;
+mod hasher;
+#[cfg(feature = "std")]
+mod io;
+mod mac;
+mod output;
+mod params;
+mod permutation;
+#[cfg(feature = "rand_core")]
+mod rng;
+mod state;
+#[cfg(feature = "digest")]
+mod traits;
+mod tree;
+mod wide;
+#[cfg(feature = "zeroize")]
+mod wipe;
+mod xof;
+
+pub mod kdf;
+#[cfg(feature = "alloc")]
+pub mod merkle;
+#[cfg(feature = "std")]
+pub mod password;
+#[cfg(feature = "std")]
+pub mod stream;
+
+pub use aead::{AeadError, FractAead};
+pub use algorithm::{Algorithm, ParseAlgorithmError};
+pub use duplex::FractDuplex;
+pub use fract512::Fract512;
+pub use hasher::{FractHasher, RandomFractState};
+#[cfg(feature = "std")]
+pub use io::{HashingReader, HashingWriter};
+pub use mac::FractMac;
+pub use output::{Digest256, Digest512, ParseDigestError};
+pub use params::{FractParams, FractParamsBuilder, ParamsError};
+pub use permutation::FractPermutation;
+#[cfg(feature = "rand_core")]
+pub use rng::FractRng;
+pub use state::{FractState, StateError};
+pub use tree::FractTree;
+pub use wide::FractWide;
+pub use xof::FractXof;
+
+/// Heap buffer for secret data, wiped on drop with the `zeroize` feature
+#[cfg(all(feature = "std", feature = "zeroize"))]
+type SecretVec<T> = zeroize::Zeroizing<alloc::vec::Vec<T>>;
+
+/// Heap buffer for secret data, wiped on drop with the `zeroize` feature
+#[cfg(all(feature = "std", not(feature = "zeroize")))]
+type SecretVec<T> = alloc::vec::Vec<T>;
 
 /// Rate in bytes: 128 bits (2 × u64)
 const RATE: usize = 16;
 
-/// Number of permutation rounds
+/// Number of permutation rounds in the v1 profile
 const ROUNDS: usize = 8;
 
+/// Domain byte for plain hashing (leaves the capacity untouched)
+const DOMAIN_HASH: u8 = 0x00;
+
+/// Domain flag for keyed hashing (FRACT-MAC)
+const DOMAIN_MAC: u8 = 0x01;
+
+/// Domain flag for extendable output (FRACT-XOF)
+const DOMAIN_XOF: u8 = 0x02;
+
+/// Domain flag for hashers with a customization string
+const DOMAIN_CUSTOM: u8 = 0x04;
+
+/// Domain flag for the root node of a tree hash (FRACT-Tree)
+const DOMAIN_TREE_ROOT: u8 = 0x08;
+
 /// Initialization Vector (first 256 bits of √2)
 const IV: [u64; 4] = [
     0x6a09e667f3bcc908,
@@ -28,11 +109,35 @@ pub struct Fract {
     /// Number of bytes currently in buffer
     buffer_len: usize,
     /// Total number of bytes absorbed
-    total_len: usize,
-    /// Whether the final block has been processed
-    finalized: bool,
+    total_len: u64,
+    /// Domain flags applied in the final padding
+    domain: u8,
+    /// State right after construction (after any key or customization prefix)
+    initial_state: [u64; 4],
+    /// Permutation rounds per block
+    rounds: usize,
 }
 
+/// Error returned by the fallible [`Fract`] methods
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashError {
+    /// The output buffer is neither 32 nor 64 bytes long
+    OutputLength,
+    /// The total input length would exceed 2^64 - 1 bytes
+    LengthOverflow,
+}
+
+impl core::fmt::Display for HashError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HashError::OutputLength => write!(f, "output must be 32 or 64 bytes"),
+            HashError::LengthOverflow => write!(f, "total input length overflows"),
+        }
+    }
+}
+
+impl core::error::Error for HashError {}
+
 impl Fract {
     /// Creates a new Fract-256 hasher
     pub fn new() -> Self {
@@ -41,16 +146,67 @@ impl Fract {
             buffer: [0; RATE],
             buffer_len: 0,
             total_len: 0,
-            finalized: false,
+            domain: DOMAIN_HASH,
+            initial_state: IV,
+            rounds: ROUNDS,
         }
     }
 
+    /// Creates a hasher with the rounds and IV of a parameter set
+    ///
+    /// With [`FractParams::FRACT_256_V1`] this is identical to [`Fract::new`].
+    pub fn with_params(params: &FractParams) -> Self {
+        Self {
+            state: params.iv(),
+            initial_state: params.iv(),
+            rounds: params.rounds(),
+            ..Self::new()
+        }
+    }
+
+    /// Creates a hasher with a customization string (cSHAKE-style)
+    ///
+    /// `function_name` identifies a function built on FRACT and `custom` is
+    /// a user-chosen label, e.g. `b"file-id"` vs `b"message-id"`. Both are
+    /// length-prefixed and absorbed into a derived initial state, and the
+    /// final padding carries a separate domain flag, so no customized input
+    /// pads to the same blocks as a plain FRACT-256 input or an input under
+    /// another customization.
+    /// With both strings empty this is identical to [`Fract::new`].
+    pub fn with_customization(function_name: &[u8], custom: &[u8]) -> Self {
+        let mut hasher = Self::new();
+        if function_name.is_empty() && custom.is_empty() {
+            return hasher;
+        }
+
+        hasher.update_encoded(function_name);
+        hasher.update_encoded(custom);
+        hasher.zero_pad_block();
+        hasher.domain = DOMAIN_CUSTOM;
+        hasher.initial_state = hasher.state;
+        hasher
+    }
+
     /// Absorbs data into the state
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total input length overflows a `u64`; use
+    /// [`Fract::try_update`] where panicking is not an option.
     pub fn update(&mut self, data: &[u8]) {
-        assert!(!self.finalized, "Cannot update after finalization");
+        self.try_update(data).expect("total input length overflows");
+    }
 
+    /// Absorbs data into the state, failing instead of panicking
+    ///
+    /// The only failure is a total input length above 2^64 - 1 bytes, in
+    /// which case nothing is absorbed.
+    pub fn try_update(&mut self, data: &[u8]) -> Result<(), HashError> {
         let mut bytes = data;
-        self.total_len += bytes.len();
+        self.total_len = self
+            .total_len
+            .checked_add(bytes.len() as u64)
+            .ok_or(HashError::LengthOverflow)?;
 
         // Fill buffer if not empty
         if self.buffer_len > 0 {
@@ -80,16 +236,47 @@ impl Fract {
             self.buffer[..bytes.len()].copy_from_slice(bytes);
             self.buffer_len = bytes.len();
         }
+
+        Ok(())
     }
 
     /// Finalizes and returns the hash (256-bit output)
     pub fn finalize(mut self) -> [u8; 32] {
-        if !self.finalized {
-            self.pad_and_absorb();
-            self.finalized = true;
+        self.pad_and_absorb(self.domain);
+        self.squeeze_256()
+    }
+
+    /// Finalizes and returns the hash (512-bit output)
+    pub fn finalize512(mut self) -> [u8; 64] {
+        self.pad_and_absorb(self.domain);
+        self.squeeze_512()
+    }
+
+    /// Finalizes and returns an extendable-output reader
+    pub fn finalize_xof(mut self) -> FractXof {
+        self.pad_and_absorb(self.domain | DOMAIN_XOF);
+        FractXof::new(self)
+    }
+
+    /// Writes the hash into `out` without consuming the hasher
+    ///
+    /// A 32-byte `out` receives the 256-bit hash and a 64-byte `out` the
+    /// 512-bit hash; other lengths are rejected. The hasher can keep
+    /// absorbing afterwards, e.g. for running digests of a growing log.
+    pub fn finalize_into(&self, out: &mut [u8]) -> Result<(), HashError> {
+        match out.len() {
+            32 => out.copy_from_slice(&self.clone().finalize()),
+            64 => out.copy_from_slice(&self.clone().finalize512()),
+            _ => return Err(HashError::OutputLength),
         }
+        Ok(())
+    }
 
-        self.squeeze_256()
+    /// Returns the hash (256-bit output) and resets the hasher
+    pub fn finalize_reset(&mut self) -> [u8; 32] {
+        let hash = self.clone().finalize();
+        self.reset();
+        hash
     }
 
     /// Convenience method: hash data in one shot (256-bit output)
@@ -99,11 +286,38 @@ impl Fract {
         hasher.finalize()
     }
 
-    /// Convenience method: hash data in one shot (512-bit output for enhanced quantum resistance)
+    /// Convenience method: hash data in one shot (512-bit output)
+    ///
+    /// Generic security is still bounded by the 128-bit capacity; use
+    /// [`FractWide`] for a larger one.
     pub fn hash512(data: &[u8]) -> [u8; 64] {
         let mut hasher = Self::new();
         hasher.update(data);
-        hasher.squeeze_512()
+        hasher.finalize512()
+    }
+
+    /// Returns to the state right after construction
+    ///
+    /// Keyed and customized hashers keep their key or customization.
+    pub fn reset(&mut self) {
+        self.state = self.initial_state;
+        self.buffer = [0; RATE];
+        self.buffer_len = 0;
+        self.total_len = 0;
+    }
+
+    /// Absorbs `le64(len(data)) || data`
+    fn update_encoded(&mut self, data: &[u8]) {
+        self.update(&(data.len() as u64).to_le_bytes());
+        self.update(data);
+    }
+
+    /// Absorbs zero bytes up to the next block boundary
+    fn zero_pad_block(&mut self) {
+        if self.buffer_len > 0 {
+            let padding = [0u8; RATE];
+            self.update(&padding[..RATE - self.buffer_len]);
+        }
     }
 
     /// Absorbs the current buffer block
@@ -135,42 +349,37 @@ impl Fract {
     }
 
     /// Applies padding and absorbs final block
-    fn pad_and_absorb(&mut self) {
-        // 10*1 padding on the rate portion
+    ///
+    /// The domain byte is XORed into the top byte of the capacity so that
+    /// different modes never share a final permutation input.
+    fn pad_and_absorb(&mut self, domain: u8) {
+        // 10*1 padding on the rate portion; with 15 buffered bytes both pad
+        // bits share the last byte, so the final bit is ORed in
         self.buffer[self.buffer_len] = 0x01;
         for i in self.buffer_len + 1..RATE {
             self.buffer[i] = 0x00;
         }
-        self.buffer[RATE - 1] = 0x80;
+        self.buffer[RATE - 1] |= 0x80;
+
+        // Domain separation in the capacity portion
+        self.state[3] ^= (domain as u64) << 56;
 
         self.absorb_block();
     }
 
-    /// Applies the permutation (8 rounds of Φ)
+    /// Applies the permutation (8 rounds of Φ in the v1 profile)
     fn permute(&mut self) {
-        for _ in 0..ROUNDS {
-            self.apply_phi();
+        for _ in 0..self.rounds {
+            self.state = FractPermutation::round(self.state);
         }
     }
 
-    /// Applies one round of the hyperchaotic lattice transformation Φ
-    #[inline(always)]
-    fn apply_phi(&mut self) {
-        let [s0, s1, s2, s3] = self.state;
-
-        // Hybrid Logistic-Tent Map f(x) on Z_2^64
-        let f0 = hltm(s0);
-        let f1 = hltm(s1);
-        let f2 = hltm(s2);
-        let f3 = hltm(s3);
-
-        // Coupled hyperchaotic lattice Φ with wrapping operations for cross-platform determinism
-        self.state = [
-            f0.wrapping_add((s1 >> 31) ^ (s3 << 17)),
-            f1.wrapping_add((s2 >> 23) ^ (s0 << 11)),
-            f2.wrapping_add((s3 >> 47) ^ (s1 << 29)),
-            f3.wrapping_add((s0 >> 13) ^ (s2 << 5)),
-        ];
+    /// Returns the rate portion of the state as bytes
+    fn rate_bytes(&self) -> [u8; RATE] {
+        let mut block = [0u8; RATE];
+        block[0..8].copy_from_slice(&self.state[0].to_le_bytes());
+        block[8..16].copy_from_slice(&self.state[1].to_le_bytes());
+        block
     }
 
     /// Squeezes 256 bits from the state
@@ -189,7 +398,7 @@ impl Fract {
         output
     }
 
-    /// Squeezes 512 bits from the state (enhanced quantum resistance)
+    /// Squeezes 512 bits from the state
     fn squeeze_512(&mut self) -> [u8; 64] {
         let mut output = [0u8; 64];
 
@@ -222,52 +431,49 @@ impl Default for Fract {
     }
 }
 
-/// Hybrid Logistic-Tent Map  on Z_2^64
-/// f(x) = { 4x(1-x) mod 2^64 if x < 2^63
-///        { 4(2^64 - x)(x - 2^63) mod 2^64 if x >= 2^63
-#[inline(always)]
-fn hltm(x: u64) -> u64 {
-    if x < (1u64 << 63) {
-        // Logistic map variant: 4x(1-x) mod 2^64
-        let x_mod = x as u128 * 4;
-        let x_sq_mod = ((x as u128 * x as u128) >> 64) * 4;
-        (x_mod - x_sq_mod) as u64
-    } else {
-        // Tent map variant: 4(2^64 - x)(x - 2^63) mod 2^64
-        let x_prime = x ^ (1u64 << 63); // x - 2^63
-        let x_complement = (!x).wrapping_add(1); // 2^64 - x
-
-        let product = (x_prime as u128) * (x_complement as u128) * 4;
-        product as u64
+/// Compares two byte strings in time independent of their contents
+fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
     }
+
+    let diff = a
+        .iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    core::hint::black_box(diff) == 0
 }
 
 /// Hash data and return 256-bit digest in hexadecimal format
-pub fn hash_to_hex(data: &[u8]) -> String {
+#[cfg(feature = "hex")]
+pub fn hash_to_hex(data: &[u8]) -> alloc::string::String {
     let hash = Fract::hash(data);
     hex::encode(hash)
 }
 
 /// Hash data and return 512-bit digest in hexadecimal format
-pub fn hash512_to_hex(data: &[u8]) -> String {
+#[cfg(feature = "hex")]
+pub fn hash512_to_hex(data: &[u8]) -> alloc::string::String {
     let hash = Fract::hash512(data);
     hex::encode(hash)
 }
 
+/// Hash data and return the 256-bit digest tagged as `fract256-v1:<hex>`
+#[cfg(feature = "hex")]
+pub fn hash_to_tagged_hex(data: &[u8]) -> alloc::string::String {
+    alloc::format!("{}:{}", Algorithm::Fract256V1, hash_to_hex(data))
+}
+
+/// Hash data and return the 512-bit digest tagged as `fract512-v1:<hex>`
+#[cfg(feature = "hex")]
+pub fn hash512_to_tagged_hex(data: &[u8]) -> alloc::string::String {
+    alloc::format!("{}:{}", Algorithm::Fract512V1, hash512_to_hex(data))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_hltm_basic() {
-        // Test HLTM with some known values
-        let x1 = 0x0123456789abcdef;
-        let _ = hltm(x1); // Just ensure it doesn't panic
-
-        let x2 = 0xfedcba9876543210;
-        let _ = hltm(x2); // Just ensure it doesn't panic
-    }
-
     #[test]
     fn test_empty_hash() {
         let hash = Fract::hash(b"");
@@ -309,6 +515,148 @@ mod tests {
         assert_eq!(hash.len(), 64);
     }
 
+    #[test]
+    fn test_finalize512_chunked() {
+        let mut hasher = Fract::new();
+        hasher.update(b"hello ");
+        hasher.update(b"world");
+        let hash = hasher.finalize512();
+
+        let expected = Fract::hash512(b"hello world");
+        assert_eq!(hash, expected);
+    }
+
+    #[test]
+    fn test_hash512_absorbs_partial_block() {
+        // Inputs shorter than the rate must still influence the 512-bit output
+        assert_ne!(Fract::hash512(b"a"), Fract::hash512(b"b"));
+        assert_ne!(Fract::hash512(b""), Fract::hash512(&[0u8; 15]));
+    }
+
+    #[test]
+    fn test_padding_is_injective() {
+        // A trailing 0x01 never collides with the padding, whatever the
+        // length of the final block
+        for len in 0..48 {
+            let message = vec![0x61; len];
+            let mut extended = message.clone();
+            extended.push(0x01);
+            assert_ne!(
+                Fract::hash(&message),
+                Fract::hash(&extended),
+                "length {}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_customization() {
+        let file_id = Fract::with_customization(b"", b"file-id").finalize();
+        let message_id = Fract::with_customization(b"", b"message-id").finalize();
+
+        assert_ne!(file_id, message_id);
+        assert_ne!(file_id, Fract::hash(b""));
+        assert_eq!(
+            Fract::with_customization(b"", b"").finalize(),
+            Fract::hash(b"")
+        );
+    }
+
+    #[test]
+    fn test_customization_is_unambiguous() {
+        let a = Fract::with_customization(b"ab", b"c").finalize();
+        let b = Fract::with_customization(b"a", b"bc").finalize();
+        assert_ne!(a, b);
+
+        // Same absorbed blocks as a customized hasher, in the plain domain
+        let mut plain = Fract::new();
+        plain.update_encoded(b"");
+        plain.update_encoded(b"label");
+        plain.zero_pad_block();
+        assert_ne!(
+            plain.finalize(),
+            Fract::with_customization(b"", b"label").finalize()
+        );
+
+        // Within one customization, a trailing 0x01 is not mistaken for padding
+        for len in 0..48 {
+            let mut a = Fract::with_customization(b"", b"label");
+            let mut b = a.clone();
+            a.update(&vec![0x61; len]);
+            b.update(&vec![0x61; len]);
+            b.update(&[0x01]);
+            assert_ne!(a.finalize(), b.finalize(), "length {}", len);
+        }
+    }
+
+    #[test]
+    fn test_customization_chunked() {
+        let mut hasher = Fract::with_customization(b"", b"commitment");
+        hasher.update(b"hello ");
+        hasher.update(b"cat");
+
+        let mut expected = Fract::with_customization(b"", b"commitment");
+        expected.update(b"hello cat");
+        assert_eq!(hasher.finalize(), expected.finalize());
+    }
+
+    #[test]
+    fn test_finalize_into() {
+        let mut hasher = Fract::new();
+        hasher.update(b"hello");
+
+        let mut out256 = [0u8; 32];
+        let mut out512 = [0u8; 64];
+        hasher.finalize_into(&mut out256).unwrap();
+        hasher.finalize_into(&mut out512).unwrap();
+        assert_eq!(out256, Fract::hash(b"hello"));
+        assert_eq!(out512, Fract::hash512(b"hello"));
+        assert_eq!(
+            hasher.finalize_into(&mut [0u8; 48]),
+            Err(HashError::OutputLength)
+        );
+
+        // The hasher keeps absorbing after a non-consuming finalization
+        hasher.update(b" world");
+        assert_eq!(hasher.finalize(), Fract::hash(b"hello world"));
+    }
+
+    #[test]
+    fn test_finalize_reset() {
+        let mut hasher = Fract::with_customization(b"app", b"log");
+        hasher.update(b"first");
+        let first = hasher.finalize_reset();
+        hasher.update(b"second");
+        let second = hasher.finalize_reset();
+
+        let expected = |data: &[u8]| {
+            let mut hasher = Fract::with_customization(b"app", b"log");
+            hasher.update(data);
+            hasher.finalize()
+        };
+        assert_eq!(first, expected(b"first"));
+        assert_eq!(second, expected(b"second"));
+
+        hasher.update(b"discarded");
+        hasher.reset();
+        assert_eq!(hasher.finalize(), expected(b""));
+    }
+
+    #[test]
+    fn test_try_update() {
+        let mut hasher = Fract::new();
+        assert_eq!(hasher.try_update(b"hello world"), Ok(()));
+        assert_eq!(hasher.clone().finalize(), Fract::hash(b"hello world"));
+
+        // An overflowing update is rejected without absorbing anything
+        hasher.total_len = u64::MAX - 1;
+        let before = hasher.export_state();
+        assert_eq!(hasher.try_update(b"ab"), Err(HashError::LengthOverflow));
+        assert_eq!(hasher.export_state(), before);
+        assert_eq!(hasher.try_update(b"a"), Ok(()));
+    }
+
     #[test]
     fn test_avalanche() {
         // Test avalanche effect: small change should produce completely different hash
@@ -337,6 +685,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "hex")]
     #[test]
     fn test_convenience_functions() {
         let hex256 = hash_to_hex(b"test");
@@ -345,4 +694,18 @@ mod tests {
         let hex512 = hash512_to_hex(b"test");
         assert_eq!(hex512.len(), 128); // 512 bits = 128 hex chars
     }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn test_tagged_hex() {
+        let tagged = hash_to_tagged_hex(b"test");
+        assert_eq!(tagged, format!("fract256-v1:{}", hash_to_hex(b"test")));
+
+        let (algorithm, hex) = Algorithm::split_tagged(&tagged).unwrap();
+        assert_eq!(algorithm, Algorithm::Fract256V1);
+        assert_eq!(hex.parse::<Digest256>(), Ok(Fract::hash(b"test").into()));
+
+        let tagged = hash512_to_tagged_hex(b"test");
+        assert!(tagged.starts_with("fract512-v1:"));
+    }
 }
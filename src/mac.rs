@@ -0,0 +1,177 @@
+//! FRACT-MAC: keyed hashing on the FRACT sponge
+//!
+//! Key absorption and domain separation:
+//!
+//! 1. Start from the standard `IV`.
+//! 2. Absorb `le64(len(key)) || key`, zero-padded to a multiple of the
+//!    16-byte rate, so the message always starts on a fresh block.
+//! 3. Absorb the message.
+//! 4. Apply the usual 10*1 padding with the `DOMAIN_MAC` flag XORed into
+//!    the top byte of the capacity (plus `DOMAIN_XOF` for
+//!    [`FractMac::finalize_xof`]), then squeeze.
+//!
+//! The length prefix makes the key encoding injective, and the domain byte
+//! keeps MAC outputs apart from plain FRACT-256 hashes of the same bytes.
+//! Security is bounded by the 128-bit capacity; use keys of at least
+//! 16 bytes.
+
+use crate::{DOMAIN_MAC, Fract, FractXof, ct_eq};
+
+/// FRACT-MAC keyed hasher
+#[derive(Clone, Debug)]
+pub struct FractMac {
+    /// Sponge with the key block(s) already absorbed
+    pub(crate) inner: Fract,
+}
+
+impl FractMac {
+    /// Creates a new MAC instance keyed with `key`
+    pub fn new(key: &[u8]) -> Self {
+        let mut inner = Fract::new();
+        inner.update_encoded(key);
+        inner.zero_pad_block();
+        inner.domain = DOMAIN_MAC;
+        inner.initial_state = inner.state;
+
+        Self { inner }
+    }
+
+    /// Absorbs message data
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Finalizes and returns the 256-bit tag
+    pub fn finalize(self) -> [u8; 32] {
+        self.inner.finalize()
+    }
+
+    /// Finalizes and returns a keyed extendable-output reader
+    pub fn finalize_xof(self) -> FractXof {
+        self.inner.finalize_xof()
+    }
+
+    /// Finalizes and checks the result against `tag` in constant time
+    pub fn verify(self, tag: &[u8]) -> bool {
+        ct_eq(&self.finalize(), tag)
+    }
+
+    /// Convenience method: compute a tag in one shot
+    pub fn mac(key: &[u8], data: &[u8]) -> [u8; 32] {
+        let mut mac = Self::new(key);
+        mac.update(data);
+        mac.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors() {
+        let vectors: [(&[u8], &[u8], &str); 3] = [
+            (
+                b"",
+                b"",
+                "36f12070fe7849fe382295f92c38ffd39d017f9a7bd3832e7bd067743ea03d58",
+            ),
+            (
+                b"key",
+                b"hello cat",
+                "e9e179351c915f58213cf118c55b3ff0c72150492fe3ae30f43e106e7c189e28",
+            ),
+            (
+                &[0x42; 32],
+                &[0x61; 100],
+                "cd1a69e0890a39f793940765f33d0d3912b005b6056d3e81f3c443aee989cf33",
+            ),
+        ];
+
+        for (key, data, expected) in vectors {
+            assert_eq!(hex::encode(FractMac::mac(key, data)), expected);
+        }
+    }
+
+    #[test]
+    fn test_chunked() {
+        let mut mac = FractMac::new(b"secret key");
+        mac.update(b"hello ");
+        mac.update(b"cat");
+        assert_eq!(mac.finalize(), FractMac::mac(b"secret key", b"hello cat"));
+    }
+
+    #[test]
+    fn test_verify() {
+        let tag = FractMac::mac(b"secret key", b"message");
+
+        let mut mac = FractMac::new(b"secret key");
+        mac.update(b"message");
+        assert!(mac.clone().verify(&tag));
+
+        let mut wrong = tag;
+        wrong[31] ^= 1;
+        assert!(!mac.clone().verify(&wrong));
+        assert!(!mac.verify(&tag[..16]));
+    }
+
+    #[test]
+    fn test_padding_is_injective() {
+        // Appending 0x01 must not reproduce the pad of a 15-byte final block
+        let tag = FractMac::mac(b"key", b"abcdefghijklmn");
+        assert_ne!(FractMac::mac(b"key", b"abcdefghijklmn\x01"), tag);
+
+        let mut forged = FractMac::new(b"key");
+        forged.update(b"abcdefghijklmn\x01");
+        assert!(!forged.verify(&tag));
+
+        for len in 0..48 {
+            let message = vec![0x61; len];
+            let mut extended = message.clone();
+            extended.push(0x01);
+            assert_ne!(
+                FractMac::mac(b"key", &message),
+                FractMac::mac(b"key", &extended),
+                "length {}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_boundary_is_unambiguous() {
+        assert_ne!(FractMac::mac(b"ab", b"c"), FractMac::mac(b"a", b"bc"));
+        assert_ne!(FractMac::mac(b"", b"key"), FractMac::mac(b"key", b""));
+    }
+
+    #[test]
+    fn test_reset_keeps_key() {
+        let mut mac = FractMac::new(b"key");
+        mac.update(b"discarded");
+        mac.inner.reset();
+        mac.update(b"data");
+        assert_eq!(mac.finalize(), FractMac::mac(b"key", b"data"));
+    }
+
+    #[test]
+    fn test_xof_differs_from_tag() {
+        let mut mac = FractMac::new(b"secret key");
+        mac.update(b"message");
+        let tag = mac.clone().finalize();
+
+        let mut output = [0u8; 32];
+        mac.finalize_xof().squeeze(&mut output);
+        assert_ne!(output, tag);
+    }
+
+    #[test]
+    fn test_differs_from_plain_hash() {
+        // Same absorbed blocks as the MAC, but finalized in the hash domain
+        let mut hasher = Fract::new();
+        hasher.update(&0u64.to_le_bytes());
+        hasher.update(&[0u8; 8]);
+        hasher.update(b"message");
+
+        assert_ne!(FractMac::mac(b"", b"message"), hasher.finalize());
+    }
+}
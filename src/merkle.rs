@@ -0,0 +1,556 @@
+//! Merkle trees over FRACT-256 (RFC 6962 style)
+//!
+//! Leaves and interior nodes are hashed under distinct customizations, in
+//! place of RFC 6962's one-byte prefixes, so a leaf can never be passed off
+//! as an interior node and neither equals a plain FRACT-256 output:
+//!
+//! ```text
+//! leaf_hash(d)    = FRACT-256[("FRACT-Merkle", "leaf")](d)
+//! node_hash(l, r) = FRACT-256[("FRACT-Merkle", "node")](l || r)
+//! ```
+//!
+//! The tree over `n` leaves splits at the largest power of two below `n`,
+//! and the root of the empty tree is `FRACT-256("")`. Inclusion and
+//! consistency proofs follow RFC 6962 section 2.1 and are checked with the
+//! verification algorithms of RFC 9162 section 2.1.3 and 2.1.4.
+//!
+//! Proofs encode as `le64 || le64 || u8 count || count × 32-byte hashes`,
+//! where the two integers are the leaf index and tree size for inclusion
+//! proofs, and the old and new tree sizes for consistency proofs.
+
+use crate::Fract;
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+/// Length of a node hash in bytes
+pub const HASH_LEN: usize = 32;
+
+/// A leaf or node hash
+pub type Hash = [u8; HASH_LEN];
+
+/// Customization function name shared by leaf and node hashes
+const FUNCTION_NAME: &[u8] = b"FRACT-Merkle";
+
+/// Upper bound on the number of hashes in a proof (tree depth)
+const MAX_PROOF_LEN: usize = 64;
+
+/// Length of the fixed part of an encoded proof
+const PROOF_HEADER_LEN: usize = 17;
+
+/// Hashes a record into a leaf hash
+pub fn leaf_hash(data: &[u8]) -> Hash {
+    let mut hasher = Fract::with_customization(FUNCTION_NAME, b"leaf");
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// Hashes two child hashes into their parent
+pub fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Fract::with_customization(FUNCTION_NAME, b"node");
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize()
+}
+
+/// Root hash of the empty tree
+pub fn empty_root() -> Hash {
+    Fract::hash(b"")
+}
+
+/// Errors from building or decoding proofs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MerkleError {
+    /// The leaf index is not below the tree size
+    IndexOutOfRange,
+    /// The tree sizes are out of order or larger than the tree
+    InvalidSize,
+    /// The encoded proof is malformed
+    InvalidEncoding,
+}
+
+impl fmt::Display for MerkleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MerkleError::IndexOutOfRange => write!(f, "leaf index out of range"),
+            MerkleError::InvalidSize => write!(f, "invalid tree size"),
+            MerkleError::InvalidEncoding => write!(f, "invalid proof encoding"),
+        }
+    }
+}
+
+impl core::error::Error for MerkleError {}
+
+/// Append-only Merkle tree holding its leaf hashes
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// Leaf hashes in insertion order
+    leaves: Vec<Hash>,
+}
+
+impl MerkleTree {
+    /// Creates an empty tree
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a tree from records
+    pub fn from_leaves<I, T>(records: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        Self {
+            leaves: records.into_iter().map(|r| leaf_hash(r.as_ref())).collect(),
+        }
+    }
+
+    /// Appends a record and returns its leaf index
+    pub fn push(&mut self, data: &[u8]) -> u64 {
+        self.push_hash(leaf_hash(data))
+    }
+
+    /// Appends an already computed leaf hash and returns its leaf index
+    pub fn push_hash(&mut self, leaf: Hash) -> u64 {
+        self.leaves.push(leaf);
+        self.leaves.len() as u64 - 1
+    }
+
+    /// Number of leaves
+    pub fn len(&self) -> u64 {
+        self.leaves.len() as u64
+    }
+
+    /// Whether the tree has no leaves
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Leaf hash at `index`
+    pub fn leaf(&self, index: u64) -> Option<&Hash> {
+        self.leaves.get(usize::try_from(index).ok()?)
+    }
+
+    /// Root hash of the whole tree
+    pub fn root(&self) -> Hash {
+        subtree_root(&self.leaves)
+    }
+
+    /// Root hash of the tree formed by the first `tree_size` leaves
+    pub fn root_at(&self, tree_size: u64) -> Result<Hash, MerkleError> {
+        Ok(subtree_root(self.prefix(tree_size)?))
+    }
+
+    /// Proves that leaf `index` is in the tree of the first `tree_size` leaves
+    pub fn inclusion_proof(
+        &self,
+        index: u64,
+        tree_size: u64,
+    ) -> Result<InclusionProof, MerkleError> {
+        let leaves = self.prefix(tree_size)?;
+        if index >= tree_size {
+            return Err(MerkleError::IndexOutOfRange);
+        }
+
+        Ok(InclusionProof {
+            index,
+            tree_size,
+            path: inclusion_path(index as usize, leaves),
+        })
+    }
+
+    /// Proves that the tree of `old_size` leaves is a prefix of the tree of `new_size`
+    pub fn consistency_proof(
+        &self,
+        old_size: u64,
+        new_size: u64,
+    ) -> Result<ConsistencyProof, MerkleError> {
+        let leaves = self.prefix(new_size)?;
+        if old_size > new_size {
+            return Err(MerkleError::InvalidSize);
+        }
+
+        let path = if old_size == 0 || old_size == new_size {
+            Vec::new()
+        } else {
+            consistency_path(old_size as usize, leaves, true)
+        };
+
+        Ok(ConsistencyProof {
+            old_size,
+            new_size,
+            path,
+        })
+    }
+
+    /// Returns the first `tree_size` leaf hashes
+    fn prefix(&self, tree_size: u64) -> Result<&[Hash], MerkleError> {
+        if tree_size > self.len() {
+            return Err(MerkleError::InvalidSize);
+        }
+        Ok(&self.leaves[..tree_size as usize])
+    }
+}
+
+/// Proof that a leaf is included in a tree of a given size
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InclusionProof {
+    /// Index of the leaf
+    pub index: u64,
+    /// Number of leaves in the tree
+    pub tree_size: u64,
+    /// Sibling hashes from the leaf up to the root
+    pub path: Vec<Hash>,
+}
+
+impl InclusionProof {
+    /// Checks that `leaf` (a leaf hash) is at `index` in the tree with `root`
+    pub fn verify(&self, leaf: &Hash, root: &Hash) -> bool {
+        if self.index >= self.tree_size {
+            return false;
+        }
+
+        let mut f_n = self.index;
+        let mut s_n = self.tree_size - 1;
+        let mut r = *leaf;
+
+        for p in &self.path {
+            if s_n == 0 {
+                return false;
+            }
+
+            if f_n & 1 == 1 || f_n == s_n {
+                r = node_hash(p, &r);
+                while f_n & 1 == 0 && f_n != 0 {
+                    f_n >>= 1;
+                    s_n >>= 1;
+                }
+            } else {
+                r = node_hash(&r, p);
+            }
+
+            f_n >>= 1;
+            s_n >>= 1;
+        }
+
+        s_n == 0 && r == *root
+    }
+
+    /// Encodes the proof in the compact binary format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_proof(self.index, self.tree_size, &self.path)
+    }
+
+    /// Decodes a proof from the compact binary format
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MerkleError> {
+        let (index, tree_size, path) = decode_proof(bytes)?;
+        Ok(Self {
+            index,
+            tree_size,
+            path,
+        })
+    }
+}
+
+/// Proof that one tree is a prefix of a larger tree
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsistencyProof {
+    /// Number of leaves in the older tree
+    pub old_size: u64,
+    /// Number of leaves in the newer tree
+    pub new_size: u64,
+    /// Node hashes linking the two roots
+    pub path: Vec<Hash>,
+}
+
+impl ConsistencyProof {
+    /// Checks that the tree with `old_root` is a prefix of the tree with `new_root`
+    pub fn verify(&self, old_root: &Hash, new_root: &Hash) -> bool {
+        if self.old_size > self.new_size {
+            return false;
+        }
+        if self.old_size == 0 {
+            return self.path.is_empty() && *old_root == empty_root();
+        }
+        if self.old_size == self.new_size {
+            return self.path.is_empty() && old_root == new_root;
+        }
+
+        // A power-of-two old tree is a complete subtree; its root starts the path
+        let mut path = self.path.iter();
+        let first = if self.old_size.is_power_of_two() {
+            old_root
+        } else {
+            match path.next() {
+                Some(hash) => hash,
+                None => return false,
+            }
+        };
+
+        let mut f_n = self.old_size - 1;
+        let mut s_n = self.new_size - 1;
+        while f_n & 1 == 1 {
+            f_n >>= 1;
+            s_n >>= 1;
+        }
+
+        let mut f_r = *first;
+        let mut s_r = *first;
+
+        for c in path {
+            if s_n == 0 {
+                return false;
+            }
+
+            if f_n & 1 == 1 || f_n == s_n {
+                f_r = node_hash(c, &f_r);
+                s_r = node_hash(c, &s_r);
+                while f_n & 1 == 0 && f_n != 0 {
+                    f_n >>= 1;
+                    s_n >>= 1;
+                }
+            } else {
+                s_r = node_hash(&s_r, c);
+            }
+
+            f_n >>= 1;
+            s_n >>= 1;
+        }
+
+        s_n == 0 && f_r == *old_root && s_r == *new_root
+    }
+
+    /// Encodes the proof in the compact binary format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_proof(self.old_size, self.new_size, &self.path)
+    }
+
+    /// Decodes a proof from the compact binary format
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MerkleError> {
+        let (old_size, new_size, path) = decode_proof(bytes)?;
+        Ok(Self {
+            old_size,
+            new_size,
+            path,
+        })
+    }
+}
+
+/// Largest power of two strictly below `n` (for `n >= 2`)
+fn split_point(n: usize) -> usize {
+    1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+}
+
+/// MTH: root of the tree over `leaves`
+fn subtree_root(leaves: &[Hash]) -> Hash {
+    match leaves.len() {
+        0 => empty_root(),
+        1 => leaves[0],
+        n => {
+            let (left, right) = leaves.split_at(split_point(n));
+            node_hash(&subtree_root(left), &subtree_root(right))
+        }
+    }
+}
+
+/// PATH(m, D[n]): sibling hashes from leaf `m` to the root
+fn inclusion_path(m: usize, leaves: &[Hash]) -> Vec<Hash> {
+    let n = leaves.len();
+    if n <= 1 {
+        return Vec::new();
+    }
+
+    let k = split_point(n);
+    let (left, right) = leaves.split_at(k);
+    let (mut path, sibling) = if m < k {
+        (inclusion_path(m, left), subtree_root(right))
+    } else {
+        (inclusion_path(m - k, right), subtree_root(left))
+    };
+    path.push(sibling);
+    path
+}
+
+/// SUBPROOF(m, D[n], b): consistency path for the first `m` leaves
+fn consistency_path(m: usize, leaves: &[Hash], complete: bool) -> Vec<Hash> {
+    let n = leaves.len();
+    if m == n {
+        return if complete {
+            Vec::new()
+        } else {
+            vec![subtree_root(leaves)]
+        };
+    }
+
+    let k = split_point(n);
+    let (left, right) = leaves.split_at(k);
+    let (mut path, sibling) = if m <= k {
+        (consistency_path(m, left, complete), subtree_root(right))
+    } else {
+        (consistency_path(m - k, right, false), subtree_root(left))
+    };
+    path.push(sibling);
+    path
+}
+
+/// Encodes two integers and a hash path
+fn encode_proof(a: u64, b: u64, path: &[Hash]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(PROOF_HEADER_LEN + path.len() * HASH_LEN);
+    bytes.extend_from_slice(&a.to_le_bytes());
+    bytes.extend_from_slice(&b.to_le_bytes());
+    bytes.push(path.len() as u8);
+    for hash in path {
+        bytes.extend_from_slice(hash);
+    }
+    bytes
+}
+
+/// Decodes two integers and a hash path
+fn decode_proof(bytes: &[u8]) -> Result<(u64, u64, Vec<Hash>), MerkleError> {
+    if bytes.len() < PROOF_HEADER_LEN {
+        return Err(MerkleError::InvalidEncoding);
+    }
+
+    let a = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
+    let b = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+    let count = bytes[16] as usize;
+    let hashes = &bytes[PROOF_HEADER_LEN..];
+
+    if count > MAX_PROOF_LEN || hashes.len() != count * HASH_LEN {
+        return Err(MerkleError::InvalidEncoding);
+    }
+
+    let path = hashes
+        .chunks_exact(HASH_LEN)
+        .map(|hash| hash.try_into().unwrap())
+        .collect();
+    Ok((a, b, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(size: usize) -> MerkleTree {
+        MerkleTree::from_leaves((0..size).map(|i| format!("record {}", i)))
+    }
+
+    #[test]
+    fn test_vectors() {
+        assert_eq!(
+            hex::encode(leaf_hash(b"")),
+            "d48a306fd9d9d8689b06119cf69cd72c2429e2ce9f5304047535ced4021fb27c"
+        );
+        assert_eq!(
+            hex::encode(tree(7).root()),
+            "4b975dc6ce8b31e8d44e84e189973f3c9d877941c61250d67444ce82e1ffad9f"
+        );
+    }
+
+    #[test]
+    fn test_root_structure() {
+        let t = tree(3);
+        let leaves: Vec<Hash> = (0..3).map(|i| *t.leaf(i).unwrap()).collect();
+
+        let expected = node_hash(&node_hash(&leaves[0], &leaves[1]), &leaves[2]);
+        assert_eq!(t.root(), expected);
+        assert_eq!(t.root_at(1).unwrap(), leaves[0]);
+        assert_eq!(MerkleTree::new().root(), empty_root());
+        assert_ne!(leaf_hash(b"x"), Fract::hash(b"x"));
+
+        // A leaf and a node over the same 64 bytes differ
+        let [left, right] = [leaves[0], leaves[1]];
+        let mut joined = left.to_vec();
+        joined.extend_from_slice(&right);
+        assert_ne!(leaf_hash(&joined), node_hash(&left, &right));
+    }
+
+    #[test]
+    fn test_leaf_second_preimage() {
+        // A trailing 0x01 must not give a second record with the same leaf
+        for len in 0..48 {
+            let record = vec![0x72; len];
+            let mut extended = record.clone();
+            extended.push(0x01);
+            assert_ne!(leaf_hash(&record), leaf_hash(&extended), "len {}", len);
+        }
+    }
+
+    #[test]
+    fn test_inclusion_proofs() {
+        let t = tree(20);
+        for size in 1..=20 {
+            let root = t.root_at(size).unwrap();
+            for index in 0..size {
+                let proof = t.inclusion_proof(index, size).unwrap();
+                let leaf = t.leaf(index).unwrap();
+                assert!(proof.verify(leaf, &root), "index {} size {}", index, size);
+
+                // Wrong leaf, wrong index and a tampered path all fail
+                assert!(!proof.verify(&leaf_hash(b"forged"), &root));
+                let mut moved = proof.clone();
+                moved.index = (index + 1) % size;
+                assert!(size == 1 || !moved.verify(leaf, &root));
+                if let Some(first) = proof.path.first() {
+                    let mut tampered = proof.clone();
+                    tampered.path[0] = node_hash(first, first);
+                    assert!(!tampered.verify(leaf, &root));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_consistency_proofs() {
+        let t = tree(20);
+        for new_size in 0..=20 {
+            let new_root = t.root_at(new_size).unwrap();
+            for old_size in 0..=new_size {
+                let old_root = t.root_at(old_size).unwrap();
+                let proof = t.consistency_proof(old_size, new_size).unwrap();
+                assert!(
+                    proof.verify(&old_root, &new_root),
+                    "old {} new {}",
+                    old_size,
+                    new_size
+                );
+
+                if old_size > 0 && old_size < new_size {
+                    let forged = leaf_hash(b"forged");
+                    assert!(!proof.verify(&forged, &new_root));
+                    assert!(!proof.verify(&old_root, &forged));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        let t = tree(5);
+        assert_eq!(t.inclusion_proof(5, 5), Err(MerkleError::IndexOutOfRange));
+        assert_eq!(t.inclusion_proof(0, 6), Err(MerkleError::InvalidSize));
+        assert_eq!(t.consistency_proof(4, 3), Err(MerkleError::InvalidSize));
+        assert_eq!(t.root_at(6), Err(MerkleError::InvalidSize));
+    }
+
+    #[test]
+    fn test_encoding_roundtrip() {
+        let t = tree(11);
+        let inclusion = t.inclusion_proof(6, 11).unwrap();
+        let bytes = inclusion.to_bytes();
+        assert_eq!(bytes.len(), 17 + inclusion.path.len() * HASH_LEN);
+        assert_eq!(InclusionProof::from_bytes(&bytes).unwrap(), inclusion);
+
+        let consistency = t.consistency_proof(3, 11).unwrap();
+        let bytes = consistency.to_bytes();
+        assert_eq!(ConsistencyProof::from_bytes(&bytes).unwrap(), consistency);
+
+        assert_eq!(
+            InclusionProof::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(MerkleError::InvalidEncoding)
+        );
+        assert_eq!(
+            ConsistencyProof::from_bytes(&bytes[..16]),
+            Err(MerkleError::InvalidEncoding)
+        );
+    }
+}
@@ -0,0 +1,342 @@
+//! Typed digest values: `Digest256` and `Digest512`
+//!
+//! The hashers return plain byte arrays; wrapping them in [`Digest256`] or
+//! [`Digest512`] (via `From`) gives values that print as lowercase hex,
+//! parse from hex with [`str::parse`], and compare in constant time, so
+//! a digest received from outside can be checked with `==` directly.
+//!
+//! With the `serde` feature, digests serialize as a hex string in
+//! human-readable formats (JSON, TOML, ...) and as raw bytes otherwise.
+
+use crate::ct_eq;
+use core::fmt;
+use core::str::FromStr;
+
+/// Error returned when a digest cannot be parsed or converted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseDigestError {
+    /// The input has the wrong number of hex digits or bytes
+    InvalidLength,
+    /// The input contains a character that is not a hex digit
+    InvalidCharacter,
+}
+
+impl fmt::Display for ParseDigestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseDigestError::InvalidLength => write!(f, "invalid digest length"),
+            ParseDigestError::InvalidCharacter => write!(f, "invalid hex character in digest"),
+        }
+    }
+}
+
+impl core::error::Error for ParseDigestError {}
+
+/// Defines a digest newtype over `[u8; $len]`
+macro_rules! digest_type {
+    ($(#[$doc:meta])* $name:ident, $len:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy)]
+        pub struct $name([u8; $len]);
+
+        impl $name {
+            /// Digest length in bytes
+            pub const LEN: usize = $len;
+
+            /// Wraps raw digest bytes
+            pub const fn from_bytes(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+
+            /// Returns the digest bytes
+            pub const fn as_bytes(&self) -> &[u8; $len] {
+                &self.0
+            }
+
+            /// Returns the digest bytes by value
+            pub const fn to_bytes(self) -> [u8; $len] {
+                self.0
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            fn from(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl From<$name> for [u8; $len] {
+            fn from(digest: $name) -> Self {
+                digest.0
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = ParseDigestError;
+
+            fn try_from(bytes: &[u8]) -> Result<Self, ParseDigestError> {
+                bytes
+                    .try_into()
+                    .map(Self)
+                    .map_err(|_| ParseDigestError::InvalidLength)
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        /// Constant-time comparison
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                ct_eq(&self.0, &other.0)
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl core::hash::Hash for $name {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.0.hash(state);
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::LowerHex::fmt(self, f)
+            }
+        }
+
+        impl fmt::LowerHex for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut buf = [0u8; 2 * $len];
+                f.write_str(encode_hex(&self.0, &mut buf))
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!(stringify!($name), "({:x})"), self)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseDigestError;
+
+            /// Parses exactly `2 * LEN` hex digits, in either case
+            fn from_str(s: &str) -> Result<Self, ParseDigestError> {
+                let mut bytes = [0u8; $len];
+                decode_hex(s, &mut bytes)?;
+                Ok(Self(bytes))
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    let mut buf = [0u8; 2 * $len];
+                    serializer.serialize_str(encode_hex(&self.0, &mut buf))
+                } else {
+                    serializer.serialize_bytes(&self.0)
+                }
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let visitor = serde_impl::BytesVisitor::<Self>::new($len);
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(visitor)
+                } else {
+                    deserializer.deserialize_bytes(visitor)
+                }
+            }
+        }
+    };
+}
+
+digest_type!(
+    /// 256-bit FRACT digest
+    Digest256,
+    32
+);
+
+digest_type!(
+    /// 512-bit FRACT digest
+    Digest512,
+    64
+);
+
+/// Writes `bytes` as lowercase hex into `buf` and returns it as a string
+pub(crate) fn encode_hex<'a>(bytes: &[u8], buf: &'a mut [u8]) -> &'a str {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    for (pair, byte) in buf.chunks_exact_mut(2).zip(bytes) {
+        pair[0] = DIGITS[(byte >> 4) as usize];
+        pair[1] = DIGITS[(byte & 0x0f) as usize];
+    }
+
+    core::str::from_utf8(buf).expect("hex digits are ASCII")
+}
+
+/// Decodes hex digits into `out`, which must be filled exactly
+fn decode_hex(s: &str, out: &mut [u8]) -> Result<(), ParseDigestError> {
+    if s.len() != 2 * out.len() {
+        return Err(ParseDigestError::InvalidLength);
+    }
+
+    for (byte, pair) in out.iter_mut().zip(s.as_bytes().chunks_exact(2)) {
+        *byte = (hex_value(pair[0])? << 4) | hex_value(pair[1])?;
+    }
+
+    Ok(())
+}
+
+/// Value of one hex digit
+fn hex_value(digit: u8) -> Result<u8, ParseDigestError> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(ParseDigestError::InvalidCharacter),
+    }
+}
+
+/// Serde support shared by the fixed-length binary values of the crate
+#[cfg(feature = "serde")]
+pub(crate) mod serde_impl {
+    use super::decode_hex;
+    use core::fmt;
+    use core::marker::PhantomData;
+    use serde::de::{Error, Unexpected, Visitor};
+
+    /// Longest value the visitor decodes, in bytes
+    const MAX_LEN: usize = 128;
+
+    /// Accepts a hex string or raw bytes of a fixed length
+    pub(crate) struct BytesVisitor<T> {
+        len: usize,
+        marker: PhantomData<T>,
+    }
+
+    impl<T> BytesVisitor<T> {
+        pub(crate) fn new(len: usize) -> Self {
+            assert!(len <= MAX_LEN);
+            Self {
+                len,
+                marker: PhantomData,
+            }
+        }
+    }
+
+    impl<'de, T> Visitor<'de> for BytesVisitor<T>
+    where
+        T: for<'a> TryFrom<&'a [u8]>,
+    {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{} hex digits or {} bytes", 2 * self.len, self.len)
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<T, E> {
+            let mut bytes = [0u8; MAX_LEN];
+            let value = decode_hex(v, &mut bytes[..self.len])
+                .ok()
+                .and_then(|()| T::try_from(&bytes[..self.len]).ok());
+
+            // The decoded bytes may be a midstate
+            #[cfg(feature = "zeroize")]
+            zeroize::Zeroize::zeroize(&mut bytes);
+            value.ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        }
+
+        fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<T, E> {
+            if v.len() != self.len {
+                return Err(E::invalid_length(v.len(), &self));
+            }
+            T::try_from(v).map_err(|_| E::invalid_value(Unexpected::Bytes(v), &self))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Fract;
+
+    #[test]
+    fn test_display_and_parse() {
+        let digest = Digest256::from(Fract::hash(b"hello world"));
+        let text = "6e43711622a571831c32b52868efe8750b6acf9a049d0c49d0a4e954223dbe7a";
+
+        assert_eq!(digest.to_string(), text);
+        assert_eq!(format!("{:x}", digest), text);
+        assert_eq!(format!("{:?}", digest), format!("Digest256({})", text));
+        assert_eq!(text.parse::<Digest256>(), Ok(digest));
+        assert_eq!(text.to_uppercase().parse::<Digest256>(), Ok(digest));
+
+        let wide = Digest512::from(Fract::hash512(b"hello world"));
+        assert_eq!(wide.to_string(), hex::encode(wide));
+        assert_eq!(wide.to_string().parse::<Digest512>(), Ok(wide));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let text = Digest256::from([0xab; 32]).to_string();
+
+        assert_eq!(
+            text[..62].parse::<Digest256>(),
+            Err(ParseDigestError::InvalidLength)
+        );
+        assert_eq!(
+            text.parse::<Digest512>(),
+            Err(ParseDigestError::InvalidLength)
+        );
+        assert_eq!(
+            format!("{}g", &text[..63]).parse::<Digest256>(),
+            Err(ParseDigestError::InvalidCharacter)
+        );
+        // Multi-byte characters must not be split
+        assert!(format!("{}é", &text[..62]).parse::<Digest256>().is_err());
+    }
+
+    #[test]
+    fn test_bytes_conversions() {
+        let bytes = Fract::hash(b"abc");
+        let digest = Digest256::from(bytes);
+
+        assert_eq!(digest.as_ref(), &bytes);
+        assert_eq!(<[u8; 32]>::from(digest), bytes);
+        assert_eq!(Digest256::try_from(&bytes[..]), Ok(digest));
+        assert_eq!(
+            Digest256::try_from(&bytes[..31]),
+            Err(ParseDigestError::InvalidLength)
+        );
+        assert_ne!(digest, Digest256::from(Fract::hash(b"abd")));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        let digest = Digest256::from([0x5a; 32]);
+        let text = "5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a";
+
+        assert_tokens(&digest.readable(), &[Token::Str(text)]);
+        assert_tokens(&digest.compact(), &[Token::Bytes(&[0x5a; 32])]);
+
+        let json = serde_json::to_string(&Digest512::from([1; 64])).unwrap();
+        assert_eq!(json, format!("\"{}\"", "01".repeat(64)));
+        assert_eq!(
+            serde_json::from_str::<Digest512>(&json).unwrap(),
+            Digest512::from([1; 64])
+        );
+        assert!(serde_json::from_str::<Digest256>(&json).is_err());
+    }
+}
@@ -0,0 +1,278 @@
+//! Parameter sets: rounds, IV and output length
+//!
+//! [`FractParams`] describes one instance of the FRACT sponge. The named
+//! profiles [`FractParams::FRACT_256_V1`] and [`FractParams::FRACT_512_V1`]
+//! are frozen: they reproduce [`Fract::hash`] and [`Fract::hash512`] bit for
+//! bit and will not change in later releases. Other parameter sets come from
+//! [`FractParams::builder`] and are meant for research (reduced-round
+//! cryptanalysis) or for hardened profiles with more rounds.
+//!
+//! Fewer rounds than the v1 profile are refused unless the builder is told
+//! [`FractParamsBuilder::insecure_reduced_rounds`]; such parameter sets
+//! report [`FractParams::is_insecure`] and must never protect real data.
+
+use crate::{Fract, HashError, IV, ROUNDS};
+use core::fmt;
+
+/// Error returned when a parameter set is rejected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamsError {
+    /// The round count is zero or above [`FractParams::MAX_ROUNDS`]
+    InvalidRounds,
+    /// Fewer rounds than the v1 profile without opting in to an insecure set
+    InsecureRounds,
+    /// The output length is neither 32 nor 64 bytes
+    InvalidOutputLength,
+}
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamsError::InvalidRounds => write!(
+                f,
+                "rounds must be between 1 and {}",
+                FractParams::MAX_ROUNDS
+            ),
+            ParamsError::InsecureRounds => {
+                write!(
+                    f,
+                    "fewer than {} rounds is insecure and must be opted into",
+                    ROUNDS
+                )
+            }
+            ParamsError::InvalidOutputLength => write!(f, "output must be 32 or 64 bytes"),
+        }
+    }
+}
+
+impl core::error::Error for ParamsError {}
+
+/// A FRACT parameter set
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FractParams {
+    /// Permutation rounds per block
+    rounds: usize,
+    /// Initial state of the sponge
+    iv: [u64; 4],
+    /// Digest length in bytes
+    output_len: usize,
+}
+
+impl FractParams {
+    /// Most rounds a parameter set may use
+    pub const MAX_ROUNDS: usize = 64;
+
+    /// FRACT-256 v1: 8 rounds, the √2 IV and a 32-byte digest (frozen)
+    pub const FRACT_256_V1: FractParams = FractParams {
+        rounds: ROUNDS,
+        iv: IV,
+        output_len: 32,
+    };
+
+    /// FRACT-512 v1: 8 rounds, the √2 IV and a 64-byte digest (frozen)
+    pub const FRACT_512_V1: FractParams = FractParams {
+        rounds: ROUNDS,
+        iv: IV,
+        output_len: 64,
+    };
+
+    /// Starts a builder from the FRACT-256 v1 profile
+    pub fn builder() -> FractParamsBuilder {
+        FractParamsBuilder {
+            params: Self::FRACT_256_V1,
+            allow_insecure: false,
+        }
+    }
+
+    /// Permutation rounds per block
+    pub const fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// Initial state of the sponge
+    pub const fn iv(&self) -> [u64; 4] {
+        self.iv
+    }
+
+    /// Digest length in bytes
+    pub const fn output_len(&self) -> usize {
+        self.output_len
+    }
+
+    /// Whether the round count is below the v1 profile
+    ///
+    /// Insecure parameter sets exist for cryptanalysis only.
+    pub const fn is_insecure(&self) -> bool {
+        self.rounds < ROUNDS
+    }
+
+    /// Creates a hasher using these parameters
+    pub fn hasher(&self) -> Fract {
+        Fract::with_params(self)
+    }
+
+    /// Hashes `data` in one shot into `out`
+    ///
+    /// `out` must be exactly [`FractParams::output_len`] bytes long.
+    pub fn hash_into(&self, data: &[u8], out: &mut [u8]) -> Result<(), HashError> {
+        if out.len() != self.output_len {
+            return Err(HashError::OutputLength);
+        }
+
+        let mut hasher = self.hasher();
+        hasher.try_update(data)?;
+        hasher.finalize_into(out)
+    }
+}
+
+impl Default for FractParams {
+    fn default() -> Self {
+        Self::FRACT_256_V1
+    }
+}
+
+/// Builder for custom [`FractParams`]
+#[derive(Clone, Copy, Debug)]
+pub struct FractParamsBuilder {
+    params: FractParams,
+    allow_insecure: bool,
+}
+
+impl FractParamsBuilder {
+    /// Sets the number of permutation rounds per block
+    pub fn rounds(mut self, rounds: usize) -> Self {
+        self.params.rounds = rounds;
+        self
+    }
+
+    /// Sets the initial state of the sponge
+    pub fn iv(mut self, iv: [u64; 4]) -> Self {
+        self.params.iv = iv;
+        self
+    }
+
+    /// Sets the digest length: 32 or 64 bytes
+    pub fn output_len(mut self, output_len: usize) -> Self {
+        self.params.output_len = output_len;
+        self
+    }
+
+    /// Allows fewer rounds than the v1 profile
+    ///
+    /// **Insecure.** Reduced-round FRACT is for cryptanalysis only.
+    pub fn insecure_reduced_rounds(mut self) -> Self {
+        self.allow_insecure = true;
+        self
+    }
+
+    /// Validates and returns the parameter set
+    pub fn build(self) -> Result<FractParams, ParamsError> {
+        let params = self.params;
+        if params.rounds == 0 || params.rounds > FractParams::MAX_ROUNDS {
+            return Err(ParamsError::InvalidRounds);
+        }
+        if params.is_insecure() && !self.allow_insecure {
+            return Err(ParamsError::InsecureRounds);
+        }
+        if params.output_len != 32 && params.output_len != 64 {
+            return Err(ParamsError::InvalidOutputLength);
+        }
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Algorithm;
+
+    #[test]
+    fn test_v1_profiles_match_fract() {
+        let data = b"hello world";
+
+        let mut out = [0u8; 32];
+        FractParams::FRACT_256_V1.hash_into(data, &mut out).unwrap();
+        assert_eq!(out, Fract::hash(data));
+        assert_eq!(
+            hex::encode(out),
+            "6e43711622a571831c32b52868efe8750b6acf9a049d0c49d0a4e954223dbe7a"
+        );
+
+        let mut wide = [0u8; 64];
+        FractParams::FRACT_512_V1
+            .hash_into(data, &mut wide)
+            .unwrap();
+        assert_eq!(wide, Fract::hash512(data));
+
+        let built = FractParams::builder().output_len(64).build().unwrap();
+        assert_eq!(built, FractParams::FRACT_512_V1);
+        assert_eq!(built.algorithm(), Some(Algorithm::Fract512V1));
+        assert_eq!(
+            FractParams::default().algorithm(),
+            Some(Algorithm::Fract256V1)
+        );
+    }
+
+    #[test]
+    fn test_reduced_rounds() {
+        assert_eq!(
+            FractParams::builder().rounds(4).build(),
+            Err(ParamsError::InsecureRounds)
+        );
+
+        let params = FractParams::builder()
+            .rounds(4)
+            .insecure_reduced_rounds()
+            .build()
+            .unwrap();
+        assert!(params.is_insecure());
+        assert_eq!(params.algorithm(), None);
+
+        let mut out = [0u8; 32];
+        params.hash_into(b"abc", &mut out).unwrap();
+        assert_ne!(out, Fract::hash(b"abc"));
+    }
+
+    #[test]
+    fn test_hardened_and_custom_iv() {
+        let hardened = FractParams::builder().rounds(16).build().unwrap();
+        assert!(!hardened.is_insecure());
+
+        let custom_iv = FractParams::builder().iv([1, 2, 3, 4]).build().unwrap();
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        hardened.hash_into(b"abc", &mut a).unwrap();
+        custom_iv.hash_into(b"abc", &mut b).unwrap();
+        assert_ne!(a, Fract::hash(b"abc"));
+        assert_ne!(b, Fract::hash(b"abc"));
+        assert_ne!(a, b);
+
+        // Incremental and one-shot hashing agree
+        let mut hasher = hardened.hasher();
+        hasher.update(b"a");
+        hasher.update(b"bc");
+        assert_eq!(hasher.finalize(), a);
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        assert_eq!(
+            FractParams::builder().rounds(0).build(),
+            Err(ParamsError::InvalidRounds)
+        );
+        assert_eq!(
+            FractParams::builder()
+                .rounds(FractParams::MAX_ROUNDS + 1)
+                .build(),
+            Err(ParamsError::InvalidRounds)
+        );
+        assert_eq!(
+            FractParams::builder().output_len(48).build(),
+            Err(ParamsError::InvalidOutputLength)
+        );
+        assert_eq!(
+            FractParams::FRACT_256_V1.hash_into(b"abc", &mut [0u8; 64]),
+            Err(HashError::OutputLength)
+        );
+    }
+}
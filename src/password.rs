@@ -0,0 +1,463 @@
+//! Memory-hard password hashing (Balloon hashing with FRACT-256)
+//!
+//! Each of the `p` lanes runs Balloon hashing (Boneh, Corrigan-Gibbs,
+//! Schechter) over `m / p` KiB of 32-byte blocks for `t` rounds, with a
+//! customized FRACT-256 as the compression function and `salt || le32(lane)`
+//! as the lane salt. The lane outputs are XORed and hashed together with
+//! the password and salt into the final 32-byte hash.
+//!
+//! Hashes are encoded as PHC strings:
+//!
+//! ```text
+//! $fract-pw$v=1$m=2048,t=3,p=1$<salt>$<hash>
+//! ```
+//!
+//! where `m` is in KiB and salt and hash use unpadded standard base64.
+
+use crate::{Fract, SecretVec, ct_eq};
+use std::fmt;
+
+/// Algorithm identifier in PHC strings
+pub const ALGORITHM: &str = "fract-pw";
+
+/// Algorithm version
+pub const VERSION: u32 = 1;
+
+/// Output hash length in bytes
+pub const HASH_LEN: usize = 32;
+
+/// Minimum salt length in bytes
+pub const MIN_SALT_LEN: usize = 8;
+
+/// Maximum salt length in bytes
+pub const MAX_SALT_LEN: usize = 64;
+
+/// Recommended salt length in bytes
+pub const RECOMMENDED_SALT_LEN: usize = 16;
+
+/// Minimum memory per lane in KiB
+const MIN_LANE_M_COST: u32 = 8;
+
+/// Maximum total memory in KiB (4 GiB)
+const MAX_M_COST: u32 = 4 * 1024 * 1024;
+
+/// Maximum number of lanes
+const MAX_P_COST: u32 = 255;
+
+/// Maximum number of passes, so a PHC string cannot demand unbounded work
+const MAX_T_COST: u32 = 1024;
+
+/// Block size in bytes (one FRACT-256 output)
+const BLOCK_LEN: usize = 32;
+
+/// Number of pseudorandom dependencies mixed into each block per round
+const DELTA: u64 = 3;
+
+/// Cost parameters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    /// Total memory in KiB, split evenly across lanes
+    pub m_cost: u32,
+    /// Number of passes over memory
+    pub t_cost: u32,
+    /// Number of independent lanes, computed in parallel
+    pub p_cost: u32,
+}
+
+impl Params {
+    /// Default parameters: 2 MiB, 3 passes, 1 lane
+    pub const DEFAULT: Params = Params {
+        m_cost: 2048,
+        t_cost: 3,
+        p_cost: 1,
+    };
+
+    /// Creates and validates a parameter set
+    pub fn new(m_cost: u32, t_cost: u32, p_cost: u32) -> Result<Self, PasswordError> {
+        let params = Self {
+            m_cost,
+            t_cost,
+            p_cost,
+        };
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Checks that the parameters are within supported bounds
+    fn validate(&self) -> Result<(), PasswordError> {
+        if self.p_cost == 0 || self.p_cost > MAX_P_COST {
+            return Err(PasswordError::InvalidParams("p must be between 1 and 255"));
+        }
+        if self.t_cost == 0 || self.t_cost > MAX_T_COST {
+            return Err(PasswordError::InvalidParams("t must be between 1 and 1024"));
+        }
+        if self.m_cost > MAX_M_COST {
+            return Err(PasswordError::InvalidParams("m must be at most 4 GiB"));
+        }
+        if self.m_cost < MIN_LANE_M_COST * self.p_cost {
+            return Err(PasswordError::InvalidParams(
+                "m must be at least 8 KiB per lane",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Number of blocks in each lane
+    fn lane_blocks(&self) -> usize {
+        (self.m_cost / self.p_cost) as usize * 1024 / BLOCK_LEN
+    }
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Errors from hashing or verifying passwords
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PasswordError {
+    /// The password does not match the hash
+    Mismatch,
+    /// The PHC string is malformed
+    InvalidFormat(&'static str),
+    /// The PHC string names another algorithm or version
+    Unsupported,
+    /// The cost parameters are out of bounds
+    InvalidParams(&'static str),
+    /// The salt is shorter than 8 or longer than 64 bytes
+    InvalidSalt,
+}
+
+impl fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordError::Mismatch => write!(f, "password does not match"),
+            PasswordError::InvalidFormat(reason) => write!(f, "invalid PHC string: {}", reason),
+            PasswordError::Unsupported => write!(f, "unsupported algorithm or version"),
+            PasswordError::InvalidParams(reason) => write!(f, "invalid parameters: {}", reason),
+            PasswordError::InvalidSalt => write!(f, "salt must be 8 to 64 bytes"),
+        }
+    }
+}
+
+impl core::error::Error for PasswordError {}
+
+/// Hashes `password` and returns the raw 32-byte hash
+pub fn hash_raw(
+    password: &[u8],
+    salt: &[u8],
+    params: &Params,
+) -> Result<[u8; HASH_LEN], PasswordError> {
+    params.validate()?;
+    if !(MIN_SALT_LEN..=MAX_SALT_LEN).contains(&salt.len()) {
+        return Err(PasswordError::InvalidSalt);
+    }
+
+    let compress = Fract::with_customization(b"FRACT-Balloon", b"");
+    let blocks = params.lane_blocks();
+
+    let mut combined = [0u8; BLOCK_LEN];
+    let lanes = SecretVec::from(if params.p_cost == 1 {
+        vec![balloon(&compress, password, salt, 0, blocks, params.t_cost)]
+    } else {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..params.p_cost)
+                .map(|lane| {
+                    let compress = &compress;
+                    scope.spawn(move || {
+                        balloon(compress, password, salt, lane, blocks, params.t_cost)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        })
+    });
+
+    for lane in lanes.iter() {
+        for (acc, byte) in combined.iter_mut().zip(lane) {
+            *acc ^= byte;
+        }
+    }
+
+    let mut hasher = Fract::with_customization(b"FRACT-Balloon", b"final");
+    hasher.update_encoded(password);
+    hasher.update_encoded(salt);
+    hasher.update(&combined);
+    Ok(hasher.finalize())
+}
+
+/// Hashes `password` and returns a PHC string
+pub fn hash_encoded(
+    password: &[u8],
+    salt: &[u8],
+    params: &Params,
+) -> Result<String, PasswordError> {
+    let hash = hash_raw(password, salt, params)?;
+
+    Ok(format!(
+        "${}$v={}$m={},t={},p={}${}${}",
+        ALGORITHM,
+        VERSION,
+        params.m_cost,
+        params.t_cost,
+        params.p_cost,
+        b64_encode(salt),
+        b64_encode(&hash)
+    ))
+}
+
+/// Verifies `password` against a PHC string in constant time
+pub fn verify(password: &[u8], phc: &str) -> Result<(), PasswordError> {
+    let (params, salt, expected) = parse_phc(phc)?;
+    let actual = hash_raw(password, &salt, &params)?;
+
+    if ct_eq(&actual, &expected) {
+        Ok(())
+    } else {
+        Err(PasswordError::Mismatch)
+    }
+}
+
+/// Splits a PHC string into parameters, salt and hash
+fn parse_phc(phc: &str) -> Result<(Params, Vec<u8>, Vec<u8>), PasswordError> {
+    let mut fields = phc.split('$');
+    if fields.next() != Some("") {
+        return Err(PasswordError::InvalidFormat("must start with '$'"));
+    }
+
+    let (Some(algorithm), Some(version), Some(params), Some(salt), Some(hash), None) = (
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+    ) else {
+        return Err(PasswordError::InvalidFormat("wrong number of fields"));
+    };
+
+    if algorithm != ALGORITHM || version != format!("v={}", VERSION) {
+        return Err(PasswordError::Unsupported);
+    }
+
+    let mut costs = [None; 3];
+    for param in params.split(',') {
+        let (name, value) = param
+            .split_once('=')
+            .ok_or(PasswordError::InvalidFormat("malformed parameter"))?;
+        let slot = match name {
+            "m" => 0,
+            "t" => 1,
+            "p" => 2,
+            _ => return Err(PasswordError::InvalidFormat("unknown parameter")),
+        };
+        if costs[slot].is_some() {
+            return Err(PasswordError::InvalidFormat("duplicate parameter"));
+        }
+        let value = value
+            .parse::<u32>()
+            .map_err(|_| PasswordError::InvalidFormat("parameter is not a number"))?;
+        costs[slot] = Some(value);
+    }
+
+    let [Some(m_cost), Some(t_cost), Some(p_cost)] = costs else {
+        return Err(PasswordError::InvalidFormat("missing parameter"));
+    };
+    let params = Params::new(m_cost, t_cost, p_cost)?;
+
+    let salt = b64_decode(salt).ok_or(PasswordError::InvalidFormat("invalid salt encoding"))?;
+    let hash = b64_decode(hash).ok_or(PasswordError::InvalidFormat("invalid hash encoding"))?;
+    if hash.len() != HASH_LEN {
+        return Err(PasswordError::InvalidFormat("wrong hash length"));
+    }
+
+    Ok((params, salt, hash))
+}
+
+/// Runs Balloon hashing for one lane and returns its last block
+fn balloon(
+    compress: &Fract,
+    password: &[u8],
+    salt: &[u8],
+    lane: u32,
+    blocks: usize,
+    t_cost: u32,
+) -> [u8; BLOCK_LEN] {
+    let mut counter = 0u64;
+    let mut hash = |parts: &[&[u8]]| {
+        let mut hasher = compress.clone();
+        hasher.update(&counter.to_le_bytes());
+        for part in parts {
+            hasher.update(part);
+        }
+        counter += 1;
+        hasher.finalize()
+    };
+
+    let mut lane_salt = salt.to_vec();
+    lane_salt.extend_from_slice(&lane.to_le_bytes());
+    let lane_salt_len = (lane_salt.len() as u64).to_le_bytes();
+    let password_len = (password.len() as u64).to_le_bytes();
+
+    // Expand: fill the buffer from the password and salt
+    let mut buf = SecretVec::from(vec![[0u8; BLOCK_LEN]; blocks]);
+    buf[0] = hash(&[&password_len, password, &lane_salt_len, &lane_salt]);
+    for m in 1..blocks {
+        buf[m] = hash(&[&buf[m - 1]]);
+    }
+
+    // Mix: each block depends on its predecessor and DELTA pseudorandom blocks
+    for t in 0..t_cost as u64 {
+        for m in 0..blocks {
+            let prev = buf[(m + blocks - 1) % blocks];
+            buf[m] = hash(&[&prev, &buf[m]]);
+
+            for i in 0..DELTA {
+                let mut index_block = [0u8; 24];
+                index_block[0..8].copy_from_slice(&t.to_le_bytes());
+                index_block[8..16].copy_from_slice(&(m as u64).to_le_bytes());
+                index_block[16..24].copy_from_slice(&i.to_le_bytes());
+
+                let index = hash(&[&lane_salt, &index_block]);
+                let other =
+                    (u64::from_le_bytes(index[..8].try_into().unwrap()) % blocks as u64) as usize;
+                buf[m] = hash(&[&buf[m], &buf[other]]);
+            }
+        }
+    }
+
+    buf[blocks - 1]
+}
+
+/// Standard base64 alphabet
+const B64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes as unpadded standard base64
+fn b64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        for i in 0..chunk.len() + 1 {
+            out.push(B64[(bits >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+
+    out
+}
+
+/// Decodes unpadded standard base64
+fn b64_decode(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.as_bytes().chunks(4) {
+        let mut bits = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let value = B64.iter().position(|&b| b == c)? as u32;
+            bits |= value << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((bits >> (16 - 8 * i)) as u8);
+        }
+        // Reject non-canonical encodings with stray low bits
+        if bits & (0xff_ffff >> (8 * (chunk.len() - 1))) != 0 {
+            return None;
+        }
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAST: Params = Params {
+        m_cost: 16,
+        t_cost: 2,
+        p_cost: 1,
+    };
+
+    #[test]
+    fn test_vectors() {
+        let phc = hash_encoded(b"password", b"somesalt", &FAST).unwrap();
+        assert_eq!(
+            phc,
+            "$fract-pw$v=1$m=16,t=2,p=1$c29tZXNhbHQ$OEKxiUscDTj1NeMH7hkXzWJkMNfv8UOkcHvntpMKJ2M"
+        );
+
+        let params = Params::new(32, 1, 2).unwrap();
+        let hash = hash_raw(b"", &[0u8; 16], &params).unwrap();
+        assert_eq!(
+            hex::encode(hash),
+            "da24567eb823894eef07d067b8702769be92795f1d026812fec8f07f05cfe769"
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let phc = hash_encoded(b"correct horse", b"0123456789abcdef", &FAST).unwrap();
+        assert_eq!(verify(b"correct horse", &phc), Ok(()));
+        assert_eq!(verify(b"correct horsf", &phc), Err(PasswordError::Mismatch));
+    }
+
+    #[test]
+    fn test_params_change_output() {
+        let base = hash_raw(b"pw", b"saltsalt", &FAST).unwrap();
+        for params in [
+            Params::new(32, 2, 1).unwrap(),
+            Params::new(16, 3, 1).unwrap(),
+            Params::new(16, 2, 2).unwrap(),
+        ] {
+            assert_ne!(hash_raw(b"pw", b"saltsalt", &params).unwrap(), base);
+        }
+        assert_ne!(hash_raw(b"pw", b"saltsalT", &FAST).unwrap(), base);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(Params::new(8, 0, 1).is_err());
+        assert!(Params::new(8, MAX_T_COST, 1).is_ok());
+        assert!(Params::new(8, MAX_T_COST + 1, 1).is_err());
+        assert!(Params::new(8, 1, 0).is_err());
+        assert!(Params::new(8, 1, 2).is_err());
+        assert_eq!(
+            hash_raw(b"pw", b"short", &FAST),
+            Err(PasswordError::InvalidSalt)
+        );
+
+        let phc = hash_encoded(b"pw", b"saltsalt", &FAST).unwrap();
+        assert_eq!(
+            verify(b"pw", &phc.replace("fract-pw", "argon2id")),
+            Err(PasswordError::Unsupported)
+        );
+        assert_eq!(
+            verify(b"pw", &phc.replace("v=1", "v=2")),
+            Err(PasswordError::Unsupported)
+        );
+        assert!(verify(b"pw", &phc.replace("t=2", "t=2,t=3")).is_err());
+        assert!(verify(b"pw", &phc.replace("m=16", "m=99999999")).is_err());
+        assert!(matches!(
+            verify(b"pw", &phc.replace("t=2", "t=4294967295")),
+            Err(PasswordError::InvalidParams(_))
+        ));
+        assert!(verify(b"pw", "$fract-pw$v=1$m=16,t=2,p=1$c2FsdA").is_err());
+    }
+
+    #[test]
+    fn test_b64_roundtrip() {
+        for len in 0..10 {
+            let data: Vec<u8> = (0..len).map(|i| (i * 37) as u8).collect();
+            assert_eq!(b64_decode(&b64_encode(&data)).unwrap(), data);
+        }
+        assert_eq!(b64_encode(b"somesalt"), "c29tZXNhbHQ");
+        assert_eq!(b64_decode("c29tZXNhbHR"), None);
+    }
+}
@@ -0,0 +1,302 @@
+//! The FRACT permutation: 8 rounds of the coupled hyperchaotic lattice Φ
+//!
+//! Exposed so other sponge constructions (MACs, PRNGs, AEADs) can be built
+//! on the FRACT lattice without copying it; [`Fract`](crate::Fract) is one
+//! client of this API.
+
+use crate::ROUNDS;
+
+/// The FRACT permutation over a 256-bit state (4 × u64)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FractPermutation {
+    /// Internal state vector (4 × u64)
+    state: [u64; 4],
+}
+
+impl FractPermutation {
+    /// Number of rounds applied by [`FractPermutation::apply`]
+    pub const ROUNDS: usize = ROUNDS;
+
+    /// Wraps a state vector
+    pub const fn new(state: [u64; 4]) -> Self {
+        Self { state }
+    }
+
+    /// Returns the current state vector
+    pub const fn state(&self) -> [u64; 4] {
+        self.state
+    }
+
+    /// Returns the state vector for absorbing into or squeezing from it
+    pub fn state_mut(&mut self) -> &mut [u64; 4] {
+        &mut self.state
+    }
+
+    /// Applies the full permutation (8 rounds of Φ)
+    pub fn apply(&mut self) {
+        self.apply_rounds(ROUNDS);
+    }
+
+    /// Applies `rounds` rounds of Φ
+    ///
+    /// Anything below [`FractPermutation::ROUNDS`] is a reduced-round
+    /// variant for analysis only.
+    pub fn apply_rounds(&mut self, rounds: usize) {
+        for _ in 0..rounds {
+            self.state = Self::round(self.state);
+        }
+    }
+
+    /// Applies one round of the hyperchaotic lattice transformation Φ
+    #[inline(always)]
+    pub fn round(state: [u64; 4]) -> [u64; 4] {
+        let [s0, s1, s2, s3] = state;
+
+        // Hybrid Logistic-Tent Map f(x) on Z_2^64
+        let f0 = hltm(s0);
+        let f1 = hltm(s1);
+        let f2 = hltm(s2);
+        let f3 = hltm(s3);
+
+        // Coupled hyperchaotic lattice Φ with wrapping operations for cross-platform determinism
+        [
+            f0.wrapping_add((s1 >> 31) ^ (s3 << 17)),
+            f1.wrapping_add((s2 >> 23) ^ (s0 << 11)),
+            f2.wrapping_add((s3 >> 47) ^ (s1 << 29)),
+            f3.wrapping_add((s0 >> 13) ^ (s2 << 5)),
+        ]
+    }
+}
+
+impl From<[u64; 4]> for FractPermutation {
+    fn from(state: [u64; 4]) -> Self {
+        Self::new(state)
+    }
+}
+
+impl From<FractPermutation> for [u64; 4] {
+    fn from(permutation: FractPermutation) -> Self {
+        permutation.state
+    }
+}
+
+/// Hybrid Logistic-Tent Map  on Z_2^64
+/// f(x) = { 4x(1-x) mod 2^64 if x < 2^63
+///        { 4(2^64 - x)(x - 2^63) mod 2^64 if x >= 2^63
+///
+/// Branch-free: both halves are computed and the top bit of `x` selects
+/// one with a mask, so the running time does not depend on the state.
+/// The multiplications are single `mul` instructions on 64-bit targets.
+#[inline(always)]
+pub(crate) fn hltm(x: u64) -> u64 {
+    // Logistic map variant: 4(x - floor(x^2 / 2^64)) mod 2^64
+    let x_sq_hi = ((x as u128 * x as u128) >> 64) as u64;
+    let logistic = x.wrapping_sub(x_sq_hi);
+
+    // Tent map variant: 4(x - 2^63)(2^64 - x) mod 2^64
+    let tent = (x ^ (1u64 << 63)).wrapping_mul(x.wrapping_neg());
+
+    // All ones when x >= 2^63
+    let mask = 0u64.wrapping_sub(x >> 63);
+    ((logistic & !mask) | (tent & mask)) << 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IV;
+
+    /// The original branching definition of the map, kept as a reference
+    fn hltm_branching(x: u64) -> u64 {
+        if x < (1u64 << 63) {
+            let x_mod = x as u128 * 4;
+            let x_sq_mod = ((x as u128 * x as u128) >> 64) * 4;
+            (x_mod - x_sq_mod) as u64
+        } else {
+            let x_prime = x ^ (1u64 << 63);
+            let x_complement = (!x).wrapping_add(1);
+
+            let product = (x_prime as u128) * (x_complement as u128) * 4;
+            product as u64
+        }
+    }
+
+    /// SplitMix64, a small deterministic source of test inputs
+    fn splitmix(seed: &mut u64) -> u64 {
+        *seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    #[test]
+    fn test_hltm_basic() {
+        // Test HLTM with some known values
+        let x1 = 0x0123456789abcdef;
+        let _ = hltm(x1); // Just ensure it doesn't panic
+
+        let x2 = 0xfedcba9876543210;
+        let _ = hltm(x2); // Just ensure it doesn't panic
+    }
+
+    #[test]
+    fn test_hltm_matches_branching_edges() {
+        let edges = [
+            0,
+            1,
+            2,
+            (1u64 << 62) - 1,
+            1u64 << 62,
+            (1u64 << 63) - 1,
+            1u64 << 63,
+            (1u64 << 63) + 1,
+            (3u64 << 62) - 1,
+            3u64 << 62,
+            u64::MAX - 1,
+            u64::MAX,
+        ];
+
+        for x in edges {
+            assert_eq!(hltm(x), hltm_branching(x), "x = {:#018x}", x);
+        }
+    }
+
+    #[test]
+    fn test_hltm_matches_branching_random() {
+        let mut seed = 0;
+        for _ in 0..1_000_000 {
+            let x = splitmix(&mut seed);
+            assert_eq!(hltm(x), hltm_branching(x), "x = {:#018x}", x);
+        }
+    }
+
+    /// dudect-style timing test: Welch's t-test between the running times
+    /// of a fixed input class (x = 0) and random inputs
+    ///
+    /// Statistical and machine dependent, so it is ignored by default; run
+    /// it with `cargo test --release -- --ignored dudect`.
+    #[test]
+    #[ignore]
+    fn test_hltm_dudect() {
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        /// Calls per measurement, to get above the timer resolution
+        const BATCH: usize = 256;
+        /// Measurements per run
+        const SAMPLES: usize = 200_000;
+        /// |t| above this rejects constant time (dudect uses 4.5 for
+        /// "definitely not constant time"; leave some headroom for noise)
+        const THRESHOLD: f64 = 10.0;
+
+        let mut seed = 1;
+        let mut inputs = [0u64; BATCH];
+        // Running count, mean and sum of squared deviations per class
+        let mut stats = [(0f64, 0f64, 0f64); 2];
+
+        for _ in 0..SAMPLES {
+            let class = (splitmix(&mut seed) & 1) as usize;
+            for input in inputs.iter_mut() {
+                *input = if class == 0 { 0 } else { splitmix(&mut seed) };
+            }
+
+            let start = Instant::now();
+            for &x in &inputs {
+                black_box(hltm(black_box(x)));
+            }
+            let elapsed = start.elapsed().as_nanos() as f64;
+
+            // Welford's online update
+            let (n, mean, m2) = &mut stats[class];
+            *n += 1.0;
+            let delta = elapsed - *mean;
+            *mean += delta / *n;
+            *m2 += delta * (elapsed - *mean);
+        }
+
+        let [(n0, mean0, m20), (n1, mean1, m21)] = stats;
+        let var0 = m20 / (n0 - 1.0);
+        let var1 = m21 / (n1 - 1.0);
+        let t = (mean0 - mean1) / (var0 / n0 + var1 / n1).sqrt();
+
+        assert!(t.abs() < THRESHOLD, "timing leak: t = {:.2}", t);
+    }
+
+    #[test]
+    fn test_known_answers() {
+        let vectors: [([u64; 4], [u64; 4]); 3] = [
+            // The all-zero state is a fixed point of Φ
+            ([0; 4], [0; 4]),
+            (
+                [u64::MAX; 4],
+                [
+                    0xbeb56c33367d26ab,
+                    0xabdb341a3f419b06,
+                    0x7cceff080c444770,
+                    0x062ee46a55ba9bc8,
+                ],
+            ),
+            (
+                IV,
+                [
+                    0x830c84fdf27ad849,
+                    0xe4d1f05b676203e8,
+                    0xefc9e24f2825686f,
+                    0xcba37e9776fb9625,
+                ],
+            ),
+        ];
+
+        for (input, expected) in vectors {
+            let mut permutation = FractPermutation::new(input);
+            permutation.apply();
+            assert_eq!(permutation.state(), expected);
+        }
+    }
+
+    #[test]
+    fn test_single_round() {
+        let expected = [
+            0xe2eb8b1a8806c973,
+            0x562cef2882468799,
+            0x694459e343a4ae0f,
+            0xa3f1b8cf700e5d02,
+        ];
+        assert_eq!(FractPermutation::round(IV), expected);
+    }
+
+    #[test]
+    fn test_apply_matches_rounds() {
+        let mut full = FractPermutation::new(IV);
+        full.apply();
+
+        let mut stepped = FractPermutation::new(IV);
+        for _ in 0..FractPermutation::ROUNDS {
+            stepped.apply_rounds(1);
+        }
+        assert_eq!(full, stepped);
+
+        let mut identity = FractPermutation::new(IV);
+        identity.apply_rounds(0);
+        assert_eq!(identity.state(), IV);
+    }
+
+    #[test]
+    fn test_hasher_is_a_client() {
+        // Absorbing the padded empty message by hand reproduces Fract::hash(b"")
+        let mut permutation = FractPermutation::new(IV);
+        permutation.state_mut()[0] ^= 0x01;
+        permutation.state_mut()[1] ^= 0x80 << 56;
+        permutation.apply();
+
+        let mut output = [0u8; 32];
+        output[0..8].copy_from_slice(&permutation.state()[0].to_le_bytes());
+        output[8..16].copy_from_slice(&permutation.state()[1].to_le_bytes());
+        permutation.apply();
+        output[16..24].copy_from_slice(&permutation.state()[0].to_le_bytes());
+        output[24..32].copy_from_slice(&permutation.state()[1].to_le_bytes());
+
+        assert_eq!(output, crate::Fract::hash(b""));
+    }
+}
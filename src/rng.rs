@@ -0,0 +1,211 @@
+//! FRACT-DRBG: deterministic random bit generator on the FRACT duplex
+//!
+//! The generator is a [`FractDuplex`] in its own domain:
+//!
+//! - `instantiate` absorbs the entropy input, nonce and personalization
+//!   string as three framed operations, then ratchets.
+//! - `reseed` absorbs new entropy and additional input, then ratchets.
+//! - `generate` absorbs the additional input (if any), squeezes the
+//!   requested output and ratchets, so a later state compromise does not
+//!   reveal earlier output.
+//!
+//! Through [`rand_core::RngCore`], output is produced in 256-byte
+//! `generate` calls and buffered, which ratchets the state every 256
+//! bytes. The output stream does not depend on how it is split into
+//! `next_u32`, `next_u64` and `fill_bytes` calls.
+
+use crate::FractDuplex;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+
+/// Bytes produced per buffered `generate` call, i.e. the ratchet period
+const BUFFER_LEN: usize = 256;
+
+/// Sponge-based deterministic random bit generator
+#[derive(Clone, Debug)]
+pub struct FractRng {
+    /// Duplex holding the generator state
+    pub(crate) duplex: FractDuplex,
+    /// Output already generated for `RngCore` but not yet returned
+    pub(crate) buffer: [u8; BUFFER_LEN],
+    /// Number of bytes of `buffer` already returned
+    pub(crate) pos: usize,
+}
+
+impl FractRng {
+    /// Seed length in bytes for [`SeedableRng`]
+    pub const SEED_LEN: usize = 32;
+
+    /// Creates a generator from entropy, a nonce and a personalization string
+    pub fn instantiate(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Self {
+        let mut duplex = FractDuplex::new(b"FRACT-DRBG v1");
+        duplex.absorb(entropy);
+        duplex.absorb(nonce);
+        duplex.absorb(personalization);
+        duplex.ratchet();
+
+        Self {
+            duplex,
+            buffer: [0; BUFFER_LEN],
+            pos: BUFFER_LEN,
+        }
+    }
+
+    /// Mixes fresh entropy and optional additional input into the state
+    ///
+    /// Output buffered for `RngCore` is discarded.
+    pub fn reseed(&mut self, entropy: &[u8], additional: &[u8]) {
+        self.duplex.absorb(entropy);
+        self.duplex.absorb(additional);
+        self.duplex.ratchet();
+        self.pos = BUFFER_LEN;
+    }
+
+    /// Fills `out` with output, mixing in `additional` input first if non-empty
+    ///
+    /// Output buffered for `RngCore` is discarded.
+    pub fn generate(&mut self, out: &mut [u8], additional: &[u8]) {
+        self.pos = BUFFER_LEN;
+        self.generate_block(out, additional);
+    }
+
+    /// Runs one generate request without touching the buffer
+    fn generate_block(&mut self, out: &mut [u8], additional: &[u8]) {
+        if !additional.is_empty() {
+            self.duplex.absorb(additional);
+        }
+        self.duplex.squeeze(out);
+        self.duplex.ratchet();
+    }
+}
+
+impl RngCore for FractRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        let mut dst = dst;
+
+        while !dst.is_empty() {
+            if self.pos == BUFFER_LEN {
+                // Whole blocks go straight to the caller; the output is the same
+                if dst.len() >= BUFFER_LEN {
+                    let (block, rest) = dst.split_at_mut(BUFFER_LEN);
+                    self.generate_block(block, &[]);
+                    dst = rest;
+                    continue;
+                }
+
+                let mut buffer = [0u8; BUFFER_LEN];
+                self.generate_block(&mut buffer, &[]);
+                self.buffer = buffer;
+                self.pos = 0;
+            }
+
+            let take = (BUFFER_LEN - self.pos).min(dst.len());
+            dst[..take].copy_from_slice(&self.buffer[self.pos..self.pos + take]);
+            self.pos += take;
+            dst = &mut dst[take..];
+        }
+    }
+}
+
+impl CryptoRng for FractRng {}
+
+impl SeedableRng for FractRng {
+    type Seed = [u8; Self::SEED_LEN];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::instantiate(&seed, &[], &[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors() {
+        let mut rng = FractRng::from_seed([0x42; 32]);
+        assert_eq!(rng.next_u64(), 0x0725_8f97_139b_c0d5);
+        assert_eq!(rng.next_u32(), 0x4547_5a17);
+
+        let mut output = [0u8; 32];
+        let mut drbg = FractRng::instantiate(b"entropy", b"nonce", b"app");
+        drbg.generate(&mut output, b"");
+        assert_eq!(
+            hex::encode(output),
+            "b58f534ae5731bc86a9e0c057f4fc70990c16b53914dc251476894c5d6b40b3f"
+        );
+
+        drbg.generate(&mut output, b"additional");
+        assert_eq!(
+            hex::encode(output),
+            "84e89810b469e9895880effc1e05b91ac33be70c612b39339e7d04cb356716d2"
+        );
+    }
+
+    #[test]
+    fn test_output_independent_of_split() {
+        let mut expected = [0u8; 1000];
+        FractRng::seed_from_u64(7).fill_bytes(&mut expected);
+
+        let mut rng = FractRng::seed_from_u64(7);
+        let mut output = [0u8; 1000];
+        let (words, rest) = output.split_at_mut(12);
+        words[..4].copy_from_slice(&rng.next_u32().to_le_bytes());
+        words[4..].copy_from_slice(&rng.next_u64().to_le_bytes());
+        let (a, b) = rest.split_at_mut(300);
+        rng.fill_bytes(a);
+        rng.fill_bytes(b);
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_from_seed_matches_instantiate() {
+        let mut seeded = FractRng::from_seed([7; 32]);
+        let mut drbg = FractRng::instantiate(&[7; 32], &[], &[]);
+
+        let mut a = [0u8; BUFFER_LEN];
+        let mut b = [0u8; BUFFER_LEN];
+        seeded.fill_bytes(&mut a);
+        drbg.generate(&mut b, &[]);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_inputs_change_output() {
+        let generate = |rng: &mut FractRng| {
+            let mut output = [0u8; 32];
+            rng.generate(&mut output, b"");
+            output
+        };
+
+        let base = generate(&mut FractRng::instantiate(b"e", b"n", b"p"));
+        assert_ne!(generate(&mut FractRng::instantiate(b"e", b"n", b"q")), base);
+        assert_ne!(generate(&mut FractRng::instantiate(b"en", b"", b"p")), base);
+
+        let mut rng = FractRng::instantiate(b"e", b"n", b"p");
+        rng.reseed(b"fresh", b"");
+        assert_ne!(generate(&mut rng), base);
+
+        let mut output = [0u8; 32];
+        FractRng::instantiate(b"e", b"n", b"p").generate(&mut output, b"add");
+        assert_ne!(output, base);
+    }
+
+    #[test]
+    fn test_successive_outputs_differ() {
+        let mut rng = FractRng::seed_from_u64(0);
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        rng.generate(&mut a, b"");
+        rng.generate(&mut b, b"");
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,382 @@
+//! Exporting and resuming a hasher midstate
+//!
+//! [`Fract::export_state`] captures everything needed to continue hashing
+//! later, possibly in another process: the state words, the buffered bytes
+//! of the incomplete block, the byte counters and the round count.
+//! [`Fract::from_state`] validates a [`FractState`] and rebuilds the hasher,
+//! so a long stream can be checkpointed and resumed after a restart with
+//! identical output.
+//!
+//! The binary encoding (version 1) is fixed-length:
+//!
+//! ```text
+//! "FRST" || version (1) || domain (1) || buffer_len (1) || rounds (1)
+//!        || state (4 × le64) || initial_state (4 × le64)
+//!        || total_len (le64) || buffer (16)
+//! ```
+//!
+//! Buffer bytes past `buffer_len` are zero. A midstate reveals everything
+//! absorbed so far, up to one block in the clear; store it like the data.
+
+use crate::{DOMAIN_CUSTOM, DOMAIN_HASH, Fract, FractParams, RATE};
+use core::fmt;
+
+/// Magic bytes at the start of an encoded state
+const MAGIC: &[u8; 4] = b"FRST";
+
+/// Error returned when a midstate cannot be decoded or resumed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StateError {
+    /// The encoding is truncated or does not start with the magic bytes
+    InvalidEncoding,
+    /// The state was exported by an unknown format version
+    UnsupportedVersion,
+    /// The fields are inconsistent and cannot come from a hasher
+    InvalidState(&'static str),
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::InvalidEncoding => write!(f, "invalid state encoding"),
+            StateError::UnsupportedVersion => write!(f, "unsupported state version"),
+            StateError::InvalidState(reason) => write!(f, "invalid state: {}", reason),
+        }
+    }
+}
+
+impl core::error::Error for StateError {}
+
+/// Serializable midstate of a [`Fract`] hasher
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FractState {
+    /// Format version, [`FractState::VERSION`] when exported
+    pub version: u8,
+    /// Sponge state words
+    pub state: [u64; 4],
+    /// State right after construction, restored by a reset
+    pub initial_state: [u64; 4],
+    /// Domain flags applied in the final padding
+    pub domain: u8,
+    /// Bytes of the incomplete block; only `buffer[..buffer_len]` is used
+    pub buffer: [u8; RATE],
+    /// Number of buffered bytes
+    pub buffer_len: usize,
+    /// Total number of bytes absorbed, including any customization prefix
+    pub total_len: u64,
+    /// Permutation rounds per block
+    pub rounds: usize,
+}
+
+impl FractState {
+    /// Current format version
+    pub const VERSION: u8 = 1;
+
+    /// Length of the binary encoding in bytes
+    pub const ENCODED_LEN: usize = 4 + 4 + 32 + 32 + 8 + RATE;
+
+    /// Encodes the state in the fixed-length binary format
+    ///
+    /// The encoding is a plain copy of the midstate and is not wiped on
+    /// drop; with the `zeroize` feature, wrap it in `zeroize::Zeroizing`
+    /// when the absorbed data is secret.
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut out = [0u8; Self::ENCODED_LEN];
+        out[..4].copy_from_slice(MAGIC);
+        out[4] = self.version;
+        out[5] = self.domain;
+        out[6] = u8::try_from(self.buffer_len).unwrap_or(u8::MAX);
+        out[7] = u8::try_from(self.rounds).unwrap_or(u8::MAX);
+
+        let words = self.state.iter().chain(&self.initial_state);
+        for (chunk, word) in out[8..72].chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+
+        out[72..80].copy_from_slice(&self.total_len.to_le_bytes());
+        out[80..].copy_from_slice(&self.buffer);
+        out
+    }
+
+    /// Decodes a state from the binary format
+    ///
+    /// Only the framing is checked here; [`Fract::from_state`] validates
+    /// the fields.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StateError> {
+        if bytes.len() != Self::ENCODED_LEN || &bytes[..4] != MAGIC {
+            return Err(StateError::InvalidEncoding);
+        }
+        if bytes[4] != Self::VERSION {
+            return Err(StateError::UnsupportedVersion);
+        }
+
+        let word =
+            |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        let mut state = [0u64; 4];
+        let mut initial_state = [0u64; 4];
+        for i in 0..4 {
+            state[i] = word(8 + 8 * i);
+            initial_state[i] = word(40 + 8 * i);
+        }
+
+        Ok(Self {
+            version: bytes[4],
+            state,
+            initial_state,
+            domain: bytes[5],
+            buffer: bytes[80..].try_into().unwrap(),
+            buffer_len: bytes[6] as usize,
+            total_len: word(72),
+            rounds: bytes[7] as usize,
+        })
+    }
+}
+
+impl TryFrom<&[u8]> for FractState {
+    type Error = StateError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, StateError> {
+        Self::from_bytes(bytes)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FractState {
+    /// Serializes the binary encoding, as hex in human-readable formats
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+        let mut bytes = self.to_bytes();
+        let result = if serializer.is_human_readable() {
+            let mut buf = [0u8; 2 * Self::ENCODED_LEN];
+            let result = serializer.serialize_str(crate::output::encode_hex(&bytes, &mut buf));
+            #[cfg(feature = "zeroize")]
+            zeroize::Zeroize::zeroize(&mut buf);
+            result
+        } else {
+            serializer.serialize_bytes(&bytes)
+        };
+
+        // The temporary encodings hold the midstate
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut bytes);
+        result
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FractState {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let visitor = crate::output::serde_impl::BytesVisitor::<Self>::new(Self::ENCODED_LEN);
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(visitor)
+        } else {
+            deserializer.deserialize_bytes(visitor)
+        }
+    }
+}
+
+impl Fract {
+    /// Exports the midstate for resuming later with [`Fract::from_state`]
+    ///
+    /// The round count and IV of a [`FractParams`] hasher are part of the
+    /// midstate, so it resumes with the same parameters.
+    pub fn export_state(&self) -> FractState {
+        // Bytes past the buffered ones are stale; export them as zeros
+        let mut buffer = [0u8; RATE];
+        buffer[..self.buffer_len].copy_from_slice(&self.buffer[..self.buffer_len]);
+
+        FractState {
+            version: FractState::VERSION,
+            state: self.state,
+            initial_state: self.initial_state,
+            domain: self.domain,
+            buffer,
+            buffer_len: self.buffer_len,
+            total_len: self.total_len,
+            rounds: self.rounds,
+        }
+    }
+
+    /// Resumes a hasher from an exported midstate
+    ///
+    /// Rejects unknown versions and fields no hasher could have produced.
+    pub fn from_state(state: &FractState) -> Result<Self, StateError> {
+        if state.version != FractState::VERSION {
+            return Err(StateError::UnsupportedVersion);
+        }
+        if state.buffer_len >= RATE {
+            return Err(StateError::InvalidState(
+                "buffer length must be below the rate",
+            ));
+        }
+        if state.total_len % RATE as u64 != state.buffer_len as u64 {
+            return Err(StateError::InvalidState(
+                "buffer length does not match total length",
+            ));
+        }
+        if state.buffer[state.buffer_len..].iter().any(|&b| b != 0) {
+            return Err(StateError::InvalidState(
+                "bytes past the buffer length must be zero",
+            ));
+        }
+        if state.rounds == 0 || state.rounds > FractParams::MAX_ROUNDS {
+            return Err(StateError::InvalidState("rounds must be between 1 and 64"));
+        }
+        if state.domain != DOMAIN_HASH && state.domain != DOMAIN_CUSTOM {
+            return Err(StateError::InvalidState("unknown domain"));
+        }
+
+        Ok(Self {
+            state: state.state,
+            buffer: state.buffer,
+            buffer_len: state.buffer_len,
+            total_len: state.total_len,
+            domain: state.domain,
+            initial_state: state.initial_state,
+            rounds: state.rounds,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_matches_one_shot() {
+        let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        for split in [0, 1, 15, 16, 17, 500, 999, 1000] {
+            let mut hasher = Fract::new();
+            hasher.update(&data[..split]);
+            let bytes = hasher.export_state().to_bytes();
+
+            let state = FractState::from_bytes(&bytes).unwrap();
+            let mut resumed = Fract::from_state(&state).unwrap();
+            resumed.update(&data[split..]);
+            assert_eq!(resumed.finalize(), Fract::hash(&data), "split {}", split);
+        }
+    }
+
+    #[test]
+    fn test_resume_customized() {
+        let mut hasher = Fract::with_customization(b"app", b"uploads");
+        hasher.update(b"first part, ");
+        let mut resumed = Fract::from_state(&hasher.export_state()).unwrap();
+
+        hasher.update(b"second part");
+        resumed.update(b"second part");
+        assert_eq!(resumed.finalize512(), hasher.finalize512());
+    }
+
+    #[test]
+    fn test_resume_with_params() {
+        let params = FractParams::builder()
+            .rounds(16)
+            .iv([1, 2, 3, 4])
+            .build()
+            .unwrap();
+        let data: Vec<u8> = (0..100).collect();
+
+        let mut hasher = params.hasher();
+        hasher.update(&data[..37]);
+        let bytes = hasher.export_state().to_bytes();
+
+        let mut resumed = Fract::from_state(&FractState::from_bytes(&bytes).unwrap()).unwrap();
+        resumed.update(&data[37..]);
+        let mut expected = [0u8; 32];
+        params.hash_into(&data, &mut expected).unwrap();
+        assert_eq!(resumed.finalize(), expected);
+    }
+
+    #[test]
+    fn test_encoding() {
+        let mut hasher = Fract::new();
+        hasher.update(&[0x61; 40]);
+        let state = hasher.export_state();
+        let bytes = state.to_bytes();
+
+        assert_eq!(&bytes[..8], b"FRST\x01\x00\x08\x08");
+        assert_eq!(bytes[72..80], 40u64.to_le_bytes());
+        assert_eq!(FractState::from_bytes(&bytes), Ok(state));
+
+        // Stale buffer bytes are not exported
+        assert!(bytes[80 + 8..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_invalid_encoding() {
+        let bytes = Fract::new().export_state().to_bytes();
+
+        assert_eq!(
+            FractState::from_bytes(&bytes[..FractState::ENCODED_LEN - 1]),
+            Err(StateError::InvalidEncoding)
+        );
+        let mut bad_magic = bytes;
+        bad_magic[0] ^= 1;
+        assert_eq!(
+            FractState::from_bytes(&bad_magic),
+            Err(StateError::InvalidEncoding)
+        );
+        let mut bad_version = bytes;
+        bad_version[4] = 2;
+        assert_eq!(
+            FractState::from_bytes(&bad_version),
+            Err(StateError::UnsupportedVersion)
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut hasher = Fract::new();
+        hasher.update(b"abc");
+        let valid = hasher.export_state();
+        assert!(Fract::from_state(&valid).is_ok());
+
+        let invalid = [
+            FractState {
+                version: 0,
+                ..valid.clone()
+            },
+            FractState {
+                buffer_len: RATE,
+                ..valid.clone()
+            },
+            FractState {
+                total_len: 4,
+                ..valid.clone()
+            },
+            FractState {
+                buffer: [1; RATE],
+                ..valid.clone()
+            },
+            FractState {
+                domain: 0x02,
+                ..valid.clone()
+            },
+            FractState {
+                rounds: 0,
+                ..valid.clone()
+            },
+            FractState {
+                rounds: FractParams::MAX_ROUNDS + 1,
+                ..valid.clone()
+            },
+        ];
+
+        for state in invalid {
+            assert!(Fract::from_state(&state).is_err(), "{:?}", state);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut hasher = Fract::new();
+        hasher.update(b"checkpoint");
+        let state = hasher.export_state();
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(state.to_bytes())));
+        assert_eq!(serde_json::from_str::<FractState>(&json).unwrap(), state);
+    }
+}
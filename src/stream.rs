@@ -0,0 +1,590 @@
+//! Chunked, versioned file encryption on FRACT-AEAD
+//!
+//! A stream is a fixed-size [`Header`] followed by chunks. Every chunk but
+//! the last holds exactly `chunk_size` bytes of ciphertext plus a 16-byte
+//! tag; the last holds at most `chunk_size` bytes (possibly zero) plus a tag.
+//!
+//! Each chunk is sealed with [`FractAead`] under the nonce
+//! `nonce_prefix (11) || le32(counter) || last (1)` with the encoded header
+//! as associated data, so chunks cannot be reordered, dropped, duplicated
+//! or moved between streams, and a stream cut at a chunk boundary fails to
+//! decrypt because its final chunk was not sealed as the last one.
+//!
+//! Header layout (53 bytes):
+//!
+//! | Field          | Size | Notes                                   |
+//! |----------------|------|-----------------------------------------|
+//! | magic          | 8    | `b"FRACTENC"`                           |
+//! | version        | 1    | `2`                                     |
+//! | key source     | 1    | `0` key file, `1` passphrase            |
+//! | chunk size     | 4    | little-endian, plaintext bytes per chunk |
+//! | salt           | 16   | passphrase salt, zero for key files     |
+//! | m, t, p        | 12   | little-endian [`password`] costs, zero for key files |
+//! | nonce prefix   | 11   | random per stream                       |
+//!
+//! The header is read before anything is authenticated, so its chunk size
+//! and passphrase costs are bounded by [`MAX_CHUNK_SIZE`] and the
+//! `MAX_PASSPHRASE_*` constants, far below the limits of a PHC string.
+
+use crate::password::{self, Params, PasswordError};
+use crate::{FractAead, SecretVec};
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// Magic bytes at the start of every stream
+pub const MAGIC: &[u8; 8] = b"FRACTENC";
+
+/// Current format version
+pub const VERSION: u8 = 2;
+
+/// Encoded header length in bytes
+pub const HEADER_LEN: usize = 53;
+
+/// Default plaintext bytes per chunk
+pub const DEFAULT_CHUNK_SIZE: u32 = 64 * 1024;
+
+/// Largest accepted chunk size
+///
+/// The chunk size comes from an unauthenticated header and sizes the
+/// buffers allocated before the first chunk is checked, so it is bounded.
+pub const MAX_CHUNK_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Largest accepted passphrase memory cost in KiB (256 MiB)
+pub const MAX_PASSPHRASE_M_COST: u32 = 256 * 1024;
+
+/// Largest accepted number of passes over the passphrase memory
+pub const MAX_PASSPHRASE_T_COST: u32 = 16;
+
+/// Largest accepted number of passphrase lanes
+pub const MAX_PASSPHRASE_P_COST: u32 = 16;
+
+/// Salt length in bytes
+pub const SALT_LEN: usize = 16;
+
+/// Per-stream nonce prefix length in bytes
+pub const NONCE_PREFIX_LEN: usize = 11;
+
+/// Where the stream key comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeySource {
+    /// A raw 32-byte key
+    KeyFile,
+    /// A passphrase, stretched by [`password::hash_raw`] with these costs
+    /// and the header salt; see [`MAX_PASSPHRASE_M_COST`] and its siblings
+    Passphrase(Params),
+}
+
+/// Stream header
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// Where the key comes from
+    pub key_source: KeySource,
+    /// Plaintext bytes per chunk
+    pub chunk_size: u32,
+    /// Passphrase salt (zero for key files)
+    pub salt: [u8; SALT_LEN],
+    /// Random per-stream nonce prefix
+    pub nonce_prefix: [u8; NONCE_PREFIX_LEN],
+}
+
+impl Header {
+    /// Encodes the header
+    pub fn to_bytes(&self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[0..8].copy_from_slice(MAGIC);
+        bytes[8] = VERSION;
+        bytes[10..14].copy_from_slice(&self.chunk_size.to_le_bytes());
+        bytes[14..30].copy_from_slice(&self.salt);
+        match self.key_source {
+            KeySource::KeyFile => bytes[9] = 0,
+            KeySource::Passphrase(params) => {
+                bytes[9] = 1;
+                bytes[30..34].copy_from_slice(&params.m_cost.to_le_bytes());
+                bytes[34..38].copy_from_slice(&params.t_cost.to_le_bytes());
+                bytes[38..42].copy_from_slice(&params.p_cost.to_le_bytes());
+            }
+        }
+        bytes[42..53].copy_from_slice(&self.nonce_prefix);
+        bytes
+    }
+
+    /// Decodes and validates a header
+    pub fn from_bytes(bytes: &[u8; HEADER_LEN]) -> Result<Self, StreamError> {
+        if &bytes[0..8] != MAGIC {
+            return Err(StreamError::InvalidHeader("not a FRACT encrypted stream"));
+        }
+        if bytes[8] != VERSION {
+            return Err(StreamError::UnsupportedVersion(bytes[8]));
+        }
+
+        let cost = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+        let key_source = match bytes[9] {
+            0 if bytes[30..42].iter().all(|&b| b == 0) => KeySource::KeyFile,
+            0 => return Err(StreamError::InvalidHeader("costs set for a key file")),
+            1 => KeySource::Passphrase(
+                Params::new(cost(30), cost(34), cost(38))
+                    .map_err(|_| StreamError::InvalidHeader("invalid passphrase costs"))?,
+            ),
+            _ => return Err(StreamError::InvalidHeader("unknown key source")),
+        };
+        check_key_source(&key_source)?;
+
+        let chunk_size = u32::from_le_bytes(bytes[10..14].try_into().unwrap());
+        check_chunk_size(chunk_size)?;
+
+        Ok(Self {
+            key_source,
+            chunk_size,
+            salt: bytes[14..30].try_into().unwrap(),
+            nonce_prefix: bytes[42..53].try_into().unwrap(),
+        })
+    }
+
+    /// Reads and validates a header from the start of a stream
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, StreamError> {
+        let mut bytes = [0u8; HEADER_LEN];
+        reader.read_exact(&mut bytes).map_err(|e| match e.kind() {
+            io::ErrorKind::UnexpectedEof => StreamError::InvalidHeader("stream too short"),
+            _ => StreamError::Io(e),
+        })?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Builds the nonce for chunk `counter`
+    fn nonce(&self, counter: u32, last: bool) -> [u8; FractAead::NONCE_LEN] {
+        let mut nonce = [0u8; FractAead::NONCE_LEN];
+        nonce[..NONCE_PREFIX_LEN].copy_from_slice(&self.nonce_prefix);
+        nonce[11..15].copy_from_slice(&counter.to_le_bytes());
+        nonce[15] = last as u8;
+        nonce
+    }
+}
+
+/// Errors from encrypting or decrypting a stream
+#[derive(Debug)]
+pub enum StreamError {
+    /// Underlying I/O failure
+    Io(io::Error),
+    /// The header is malformed
+    InvalidHeader(&'static str),
+    /// The header names a format version this build does not know
+    UnsupportedVersion(u8),
+    /// A chunk failed authentication (wrong key or modified data)
+    Authentication {
+        /// Index of the failing chunk
+        chunk: u32,
+    },
+    /// The stream ends before its final chunk
+    Truncated,
+    /// The stream has more chunks than the nonce counter allows
+    TooLong,
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "{}", e),
+            StreamError::InvalidHeader(reason) => write!(f, "invalid header: {}", reason),
+            StreamError::UnsupportedVersion(v) => write!(f, "unsupported format version {}", v),
+            StreamError::Authentication { chunk } => {
+                write!(f, "authentication failed at chunk {}", chunk)
+            }
+            StreamError::Truncated => write!(f, "stream is truncated"),
+            StreamError::TooLong => write!(f, "stream exceeds the maximum number of chunks"),
+        }
+    }
+}
+
+impl core::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StreamError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+/// Derives a stream key from a passphrase, the header salt and its costs
+///
+/// The key is the memory-hard [`password::hash_raw`] of the passphrase, so
+/// each guess costs as much as a password hash with the same parameters.
+pub fn passphrase_key(
+    passphrase: &[u8],
+    salt: &[u8; SALT_LEN],
+    params: &Params,
+) -> Result<[u8; FractAead::KEY_LEN], PasswordError> {
+    password::hash_raw(passphrase, salt, params)
+}
+
+/// Writes `header` and the encryption of `reader` to `writer`
+///
+/// The header's chunk size must be between 1 and [`MAX_CHUNK_SIZE`], and
+/// passphrase costs within the `MAX_PASSPHRASE_*` bounds. Returns the
+/// number of plaintext bytes encrypted.
+pub fn encrypt<R: Read, W: Write>(
+    key: &[u8; FractAead::KEY_LEN],
+    header: &Header,
+    mut reader: R,
+    mut writer: W,
+) -> Result<u64, StreamError> {
+    check_chunk_size(header.chunk_size)?;
+    check_key_source(&header.key_source)?;
+
+    let ad = header.to_bytes();
+    writer.write_all(&ad)?;
+
+    let chunk_size = header.chunk_size as usize;
+    let mut current = SecretVec::from(vec![0u8; chunk_size]);
+    let mut next = SecretVec::from(vec![0u8; chunk_size]);
+    let mut len = read_full(&mut reader, &mut current)?;
+    let mut counter = 0u32;
+    let mut total = 0u64;
+
+    loop {
+        // A full chunk is only the last one if nothing follows it
+        let next_len = if len == chunk_size {
+            read_full(&mut reader, &mut next)?
+        } else {
+            0
+        };
+        let last = next_len == 0;
+
+        let nonce = header.nonce(counter, last);
+        let tag = FractAead::encrypt_in_place_detached(key, &nonce, &ad, &mut current[..len]);
+        writer.write_all(&current[..len])?;
+        writer.write_all(&tag)?;
+        total += len as u64;
+
+        if last {
+            break;
+        }
+
+        std::mem::swap(&mut current, &mut next);
+        len = next_len;
+        counter = counter.checked_add(1).ok_or(StreamError::TooLong)?;
+    }
+
+    writer.flush()?;
+    Ok(total)
+}
+
+/// Decrypts the chunks following `header` from `reader` into `writer`
+///
+/// `header` must have been read from the same stream with
+/// [`Header::read_from`]. Each chunk is authenticated before it is
+/// written. Returns the number of plaintext bytes written.
+pub fn decrypt<R: Read, W: Write>(
+    key: &[u8; FractAead::KEY_LEN],
+    header: &Header,
+    mut reader: R,
+    mut writer: W,
+) -> Result<u64, StreamError> {
+    check_chunk_size(header.chunk_size)?;
+
+    let ad = header.to_bytes();
+    let sealed_size = header.chunk_size as usize + FractAead::TAG_LEN;
+    let mut current = SecretVec::from(vec![0u8; sealed_size]);
+    let mut next = SecretVec::from(vec![0u8; sealed_size]);
+    let mut len = read_full(&mut reader, &mut current)?;
+    let mut counter = 0u32;
+    let mut total = 0u64;
+
+    loop {
+        if len < FractAead::TAG_LEN {
+            return Err(StreamError::Truncated);
+        }
+
+        let next_len = if len == sealed_size {
+            read_full(&mut reader, &mut next)?
+        } else {
+            0
+        };
+        let last = next_len == 0;
+
+        let (chunk, tag) = current[..len].split_at_mut(len - FractAead::TAG_LEN);
+        let tag: [u8; FractAead::TAG_LEN] = (&*tag).try_into().unwrap();
+
+        // Keep the final ciphertext to tell truncation apart from tampering
+        let saved = last.then(|| SecretVec::from(chunk.to_vec()));
+        let nonce = header.nonce(counter, last);
+
+        if FractAead::decrypt_in_place_detached(key, &nonce, &ad, chunk, &tag).is_err() {
+            // A final chunk that verifies as non-final means the rest was cut off
+            if let Some(mut saved) = saved {
+                let nonce = header.nonce(counter, false);
+                if FractAead::decrypt_in_place_detached(key, &nonce, &ad, &mut saved, &tag).is_ok()
+                {
+                    return Err(StreamError::Truncated);
+                }
+            }
+            return Err(StreamError::Authentication { chunk: counter });
+        }
+
+        writer.write_all(chunk)?;
+        total += chunk.len() as u64;
+
+        if last {
+            break;
+        }
+
+        std::mem::swap(&mut current, &mut next);
+        len = next_len;
+        counter = counter.checked_add(1).ok_or(StreamError::TooLong)?;
+    }
+
+    writer.flush()?;
+    Ok(total)
+}
+
+/// Rejects a zero or oversized chunk size
+fn check_chunk_size(chunk_size: u32) -> Result<(), StreamError> {
+    if chunk_size == 0 {
+        return Err(StreamError::InvalidHeader("zero chunk size"));
+    }
+    if chunk_size > MAX_CHUNK_SIZE {
+        return Err(StreamError::InvalidHeader("chunk size too large"));
+    }
+    Ok(())
+}
+
+/// Rejects passphrase costs above the stream bounds
+fn check_key_source(key_source: &KeySource) -> Result<(), StreamError> {
+    if let KeySource::Passphrase(params) = key_source
+        && (params.m_cost > MAX_PASSPHRASE_M_COST
+            || params.t_cost > MAX_PASSPHRASE_T_COST
+            || params.p_cost > MAX_PASSPHRASE_P_COST)
+    {
+        return Err(StreamError::InvalidHeader("passphrase costs too large"));
+    }
+    Ok(())
+}
+
+/// Reads until `buf` is full or the reader is exhausted
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [0x42; 32];
+
+    fn header(chunk_size: u32) -> Header {
+        Header {
+            key_source: KeySource::KeyFile,
+            chunk_size,
+            salt: [0; SALT_LEN],
+            nonce_prefix: [0x24; NONCE_PREFIX_LEN],
+        }
+    }
+
+    fn seal(data: &[u8], chunk_size: u32) -> Vec<u8> {
+        let mut output = Vec::new();
+        encrypt(&KEY, &header(chunk_size), data, &mut output).unwrap();
+        output
+    }
+
+    fn open(sealed: &[u8]) -> Result<Vec<u8>, StreamError> {
+        let mut reader = sealed;
+        let header = Header::read_from(&mut reader)?;
+        let mut output = Vec::new();
+        decrypt(&KEY, &header, reader, &mut output)?;
+        Ok(output)
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for len in [0, 1, 15, 16, 17, 48, 100] {
+            let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let sealed = seal(&data, 16);
+
+            let chunks = (len as usize).div_ceil(16).max(1);
+            assert_eq!(
+                sealed.len(),
+                HEADER_LEN + len as usize + chunks * FractAead::TAG_LEN
+            );
+            assert_eq!(open(&sealed).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_header_roundtrip() {
+        let mut original = header(DEFAULT_CHUNK_SIZE);
+        assert_eq!(Header::from_bytes(&original.to_bytes()).unwrap(), original);
+
+        original.key_source = KeySource::Passphrase(Params::new(64, 2, 2).unwrap());
+        original.salt = [7; SALT_LEN];
+        let bytes = original.to_bytes();
+        assert_eq!(&bytes[30..42], &[64, 0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0]);
+        assert_eq!(Header::from_bytes(&bytes).unwrap(), original);
+
+        // Costs are validated like a PHC string's
+        let mut bad_costs = bytes;
+        bad_costs[34..38].fill(0);
+        assert!(matches!(
+            Header::from_bytes(&bad_costs),
+            Err(StreamError::InvalidHeader(_))
+        ));
+
+        // A key file stream carries no costs
+        let mut key_file = header(DEFAULT_CHUNK_SIZE).to_bytes();
+        key_file[30] = 1;
+        assert!(matches!(
+            Header::from_bytes(&key_file),
+            Err(StreamError::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn test_truncation_is_detected() {
+        let sealed = seal(&[0x61; 48], 16);
+        let chunk = 16 + FractAead::TAG_LEN;
+
+        // Cut at a chunk boundary
+        let cut = &sealed[..HEADER_LEN + 2 * chunk];
+        assert!(matches!(open(cut), Err(StreamError::Truncated)));
+
+        // Cut inside a chunk
+        let cut = &sealed[..sealed.len() - 5];
+        assert!(open(cut).is_err());
+
+        // Header only
+        assert!(matches!(
+            open(&sealed[..HEADER_LEN]),
+            Err(StreamError::Truncated)
+        ));
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let sealed = seal(&[0x61; 48], 16);
+
+        let mut tampered = sealed.clone();
+        tampered[HEADER_LEN + 20] ^= 1;
+        assert!(matches!(
+            open(&tampered),
+            Err(StreamError::Authentication { chunk: 0 })
+        ));
+
+        // Changing the header changes the associated data of every chunk
+        let mut tampered = sealed.clone();
+        tampered[HEADER_LEN - 1] ^= 1;
+        assert!(matches!(
+            open(&tampered),
+            Err(StreamError::Authentication { chunk: 0 })
+        ));
+
+        let mut extended = sealed.clone();
+        extended.extend_from_slice(&[0; 20]);
+        assert!(open(&extended).is_err());
+
+        let mut wrong_version = sealed;
+        wrong_version[8] = 1;
+        assert!(matches!(
+            open(&wrong_version),
+            Err(StreamError::UnsupportedVersion(1))
+        ));
+    }
+
+    #[test]
+    fn test_chunk_size_is_bounded() {
+        let mut bytes = header(DEFAULT_CHUNK_SIZE).to_bytes();
+        bytes[10..14].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            Header::from_bytes(&bytes),
+            Err(StreamError::InvalidHeader(_))
+        ));
+        assert!(matches!(open(&bytes), Err(StreamError::InvalidHeader(_))));
+
+        bytes[10..14].copy_from_slice(&0u32.to_le_bytes());
+        assert!(Header::from_bytes(&bytes).is_err());
+
+        let mut output = Vec::new();
+        let result = encrypt(&KEY, &header(MAX_CHUNK_SIZE + 1), &b"abc"[..], &mut output);
+        assert!(matches!(result, Err(StreamError::InvalidHeader(_))));
+        assert!(output.is_empty());
+
+        // A header built by hand is checked before any buffer is allocated
+        let result = decrypt(&KEY, &header(u32::MAX), &b""[..], &mut output);
+        assert!(matches!(result, Err(StreamError::InvalidHeader(_))));
+        let result = decrypt(&KEY, &header(0), &b""[..], &mut output);
+        assert!(matches!(result, Err(StreamError::InvalidHeader(_))));
+
+        assert_eq!(
+            Header::from_bytes(&header(MAX_CHUNK_SIZE).to_bytes()).unwrap(),
+            header(MAX_CHUNK_SIZE)
+        );
+    }
+
+    #[test]
+    fn test_passphrase_costs_are_bounded() {
+        let mut original = header(DEFAULT_CHUNK_SIZE);
+        original.key_source = KeySource::Passphrase(
+            Params::new(
+                MAX_PASSPHRASE_M_COST,
+                MAX_PASSPHRASE_T_COST,
+                MAX_PASSPHRASE_P_COST,
+            )
+            .unwrap(),
+        );
+        let bytes = original.to_bytes();
+        assert_eq!(Header::from_bytes(&bytes).unwrap(), original);
+
+        // Costs a PHC string may carry, but a stream header may not
+        for (offset, cost) in [
+            (30, MAX_PASSPHRASE_M_COST + 1),
+            (34, MAX_PASSPHRASE_T_COST + 1),
+            (38, MAX_PASSPHRASE_P_COST + 1),
+        ] {
+            let mut forged = bytes;
+            forged[offset..offset + 4].copy_from_slice(&cost.to_le_bytes());
+            assert!(matches!(
+                Header::from_bytes(&forged),
+                Err(StreamError::InvalidHeader(_))
+            ));
+        }
+
+        let mut greedy = original;
+        greedy.key_source = KeySource::Passphrase(Params::new(1 << 20, 1, 1).unwrap());
+        let mut output = Vec::new();
+        let result = encrypt(&KEY, &greedy, &b"abc"[..], &mut output);
+        assert!(matches!(result, Err(StreamError::InvalidHeader(_))));
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_passphrase_key() {
+        let params = Params::new(64, 1, 1).unwrap();
+        let key = passphrase_key(b"hunter2", &[0; SALT_LEN], &params).unwrap();
+        assert_eq!(
+            key,
+            password::hash_raw(b"hunter2", &[0; SALT_LEN], &params).unwrap()
+        );
+        assert_ne!(
+            key,
+            passphrase_key(b"hunter2", &[1; SALT_LEN], &params).unwrap()
+        );
+        assert_ne!(
+            key,
+            passphrase_key(b"hunter2", &[0; SALT_LEN], &Params::new(64, 2, 1).unwrap()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! RustCrypto `digest` trait implementations
+//!
+//! [`Fract`] implements the traits behind `digest::Digest` with a 32-byte
+//! output, [`Fract512`] with a 64-byte output, and `Fract` also implements
+//! `ExtendableOutput` with [`FractXof`] as the reader. The block size is
+//! the 16-byte rate. `Reset` returns a hasher to its state right after
+//! construction, so keyed and customized hashers stay keyed and customized.
+
+use crate::{Fract, Fract512, FractXof, RATE};
+use digest::consts::{U16, U32, U64};
+use digest::{
+    ExtendableOutput, ExtendableOutputReset, FixedOutput, FixedOutputReset, HashMarker, Output,
+    OutputSizeUser, Reset, Update, XofReader, core_api::BlockSizeUser,
+};
+
+const _: () = assert!(RATE == 16);
+
+impl HashMarker for Fract {}
+
+impl BlockSizeUser for Fract {
+    type BlockSize = U16;
+}
+
+impl OutputSizeUser for Fract {
+    type OutputSize = U32;
+}
+
+impl Update for Fract {
+    fn update(&mut self, data: &[u8]) {
+        Fract::update(self, data);
+    }
+}
+
+impl FixedOutput for Fract {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.finalize());
+    }
+}
+
+impl Reset for Fract {
+    fn reset(&mut self) {
+        Fract::reset(self);
+    }
+}
+
+impl FixedOutputReset for Fract {
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.finalize_reset());
+    }
+}
+
+impl ExtendableOutput for Fract {
+    type Reader = FractXof;
+
+    fn finalize_xof(self) -> FractXof {
+        Fract::finalize_xof(self)
+    }
+}
+
+impl ExtendableOutputReset for Fract {
+    fn finalize_xof_reset(&mut self) -> FractXof {
+        let reader = self.clone().finalize_xof();
+        Fract::reset(self);
+        reader
+    }
+}
+
+impl XofReader for FractXof {
+    fn read(&mut self, buffer: &mut [u8]) {
+        self.squeeze(buffer);
+    }
+}
+
+impl HashMarker for Fract512 {}
+
+impl BlockSizeUser for Fract512 {
+    type BlockSize = U16;
+}
+
+impl OutputSizeUser for Fract512 {
+    type OutputSize = U64;
+}
+
+impl Update for Fract512 {
+    fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+}
+
+impl FixedOutput for Fract512 {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.inner.finalize512());
+    }
+}
+
+impl Reset for Fract512 {
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+impl FixedOutputReset for Fract512 {
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.inner.clone().finalize512());
+        self.inner.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use digest::Digest;
+
+    /// Hashes through the generic `Digest` API only
+    fn digest_generic<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+        let mut hasher = D::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        hasher.finalize().to_vec()
+    }
+
+    /// Squeezes through the generic XOF traits only
+    fn xof_generic<X: ExtendableOutput + Default>(data: &[u8], len: usize) -> Vec<u8> {
+        let mut hasher = X::default();
+        hasher.update(data);
+        let mut reader = hasher.finalize_xof();
+        let mut output = vec![0u8; len];
+        reader.read(&mut output);
+        output
+    }
+
+    #[test]
+    fn test_digest_matches_inherent_api() {
+        assert_eq!(
+            digest_generic::<Fract>(&[b"hello ", b"world"]),
+            Fract::hash(b"hello world")
+        );
+        assert_eq!(
+            digest_generic::<Fract512>(&[b"hello ", b"world"]),
+            Fract::hash512(b"hello world")
+        );
+        assert_eq!(<Fract as Digest>::output_size(), 32);
+        assert_eq!(<Fract512 as Digest>::output_size(), 64);
+        assert_eq!(Fract::digest(b"abc")[..], Fract::hash(b"abc"));
+    }
+
+    #[test]
+    fn test_finalize_reset() {
+        let mut hasher = <Fract512 as Digest>::new();
+        Digest::update(&mut hasher, b"first");
+        let first = hasher.finalize_reset();
+        Digest::update(&mut hasher, b"second");
+        let second = hasher.finalize_reset();
+
+        assert_eq!(first[..], Fract::hash512(b"first"));
+        assert_eq!(second[..], Fract::hash512(b"second"));
+    }
+
+    #[test]
+    fn test_reset_keeps_prefix() {
+        let mut hasher = Fract::with_customization(b"", b"label");
+        Update::update(&mut hasher, b"discarded");
+        Reset::reset(&mut hasher);
+        Update::update(&mut hasher, b"data");
+
+        let mut expected = Fract::with_customization(b"", b"label");
+        expected.update(b"data");
+        assert_eq!(hasher.finalize(), expected.finalize());
+    }
+
+    #[test]
+    fn test_xof() {
+        let output = xof_generic::<Fract>(b"hello cat", 100);
+
+        let mut expected = vec![0u8; 100];
+        let mut hasher = Fract::new();
+        hasher.update(b"hello cat");
+        hasher.finalize_xof().squeeze(&mut expected);
+        assert_eq!(output, expected);
+
+        let mut hasher = Fract::new();
+        Update::update(&mut hasher, b"hello cat");
+        let mut reader = hasher.finalize_xof_reset();
+        let mut first = [0u8; 100];
+        XofReader::read(&mut reader, &mut first);
+        assert_eq!(first[..], expected[..]);
+        assert_eq!(hasher.finalize(), Fract::hash(b""));
+    }
+}
@@ -0,0 +1,351 @@
+//! FRACT-Tree: tree hashing mode for large inputs
+//!
+//! The input is split into 8 KiB chunks. Each chunk is a leaf hashed
+//! independently as `le64(chunk index) || chunk` under the customization
+//! `("FRACT-Tree", "leaf")`, giving a 32-byte chaining value. Chaining
+//! values are combined pairwise under `("FRACT-Tree", "parent")` into a
+//! binary tree whose left subtree always holds the largest power of two
+//! number of chunks, as in BLAKE3. The root node (a parent, or the only
+//! leaf for inputs of at most one chunk) is finalized with the
+//! `DOMAIN_TREE_ROOT` flag, so no chaining value equals a root output.
+//!
+//! [`FractTree::update`] keeps a stack of at most one chaining value per
+//! tree level and hashes on one thread. With the `parallel` feature,
+//! [`FractTree::update_parallel`] hashes the chunks of each batch on the
+//! rayon thread pool; both produce identical output.
+
+use crate::{DOMAIN_TREE_ROOT, Fract};
+
+/// Maximum tree depth: one stack entry per bit of the 64-bit chunk counter
+const MAX_DEPTH: usize = 64;
+
+/// Tree hasher state
+#[derive(Clone, Debug)]
+pub struct FractTree {
+    /// Initial state for leaves
+    leaf: Fract,
+    /// Initial state for parents
+    parent: Fract,
+    /// Hasher of the current (last seen) chunk
+    chunk: Fract,
+    /// Number of bytes absorbed into the current chunk
+    chunk_len: usize,
+    /// Index of the current chunk
+    chunk_index: u64,
+    /// Chaining values of completed subtrees, one per tree level at most
+    stack: [[u8; 32]; MAX_DEPTH],
+    /// Number of chaining values on the stack
+    stack_len: usize,
+}
+
+impl FractTree {
+    /// Leaf chunk size in bytes
+    pub const CHUNK_LEN: usize = 8192;
+
+    /// Creates a new tree hasher
+    pub fn new() -> Self {
+        let leaf = Fract::with_customization(b"FRACT-Tree", b"leaf");
+        let parent = Fract::with_customization(b"FRACT-Tree", b"parent");
+        let chunk = leaf_hasher(&leaf, 0);
+
+        Self {
+            leaf,
+            parent,
+            chunk,
+            chunk_len: 0,
+            chunk_index: 0,
+            stack: [[0; 32]; MAX_DEPTH],
+            stack_len: 0,
+        }
+    }
+
+    /// Absorbs data on the current thread
+    pub fn update(&mut self, data: &[u8]) {
+        let mut bytes = data;
+
+        while !bytes.is_empty() {
+            // A full chunk is only completed once more input arrives, so the
+            // last chunk can still become the root
+            if self.chunk_len == Self::CHUNK_LEN {
+                let index = self.chunk_index + 1;
+                let chunk = core::mem::replace(&mut self.chunk, leaf_hasher(&self.leaf, index));
+                self.push_cv(chunk.finalize(), index);
+                self.chunk_len = 0;
+                self.chunk_index = index;
+            }
+
+            let take = (Self::CHUNK_LEN - self.chunk_len).min(bytes.len());
+            self.chunk.update(&bytes[..take]);
+            self.chunk_len += take;
+            bytes = &bytes[take..];
+        }
+    }
+
+    /// Absorbs data, hashing whole chunks in parallel on the rayon pool
+    ///
+    /// The output is identical to [`FractTree::update`]. Larger inputs give
+    /// more parallelism; pass several megabytes per call where possible.
+    #[cfg(feature = "parallel")]
+    pub fn update_parallel(&mut self, data: &[u8]) {
+        use rayon::prelude::*;
+
+        /// Chunks hashed per batch, bounding the chaining values held at once
+        const BATCH_CHUNKS: usize = 1024;
+
+        // Top up the current chunk so the rest starts on a chunk boundary
+        let fill = (Self::CHUNK_LEN - self.chunk_len).min(data.len());
+        self.update(&data[..fill]);
+        let bytes = &data[fill..];
+
+        // Whole chunks followed by at least one more byte are never the root
+        let whole = bytes.len().saturating_sub(1) / Self::CHUNK_LEN;
+        let (parallel, rest) = bytes.split_at(whole * Self::CHUNK_LEN);
+
+        for batch in parallel.chunks(BATCH_CHUNKS * Self::CHUNK_LEN) {
+            // The current chunk is full here; complete it before the batch
+            let first = self.chunk_index + 1;
+            let chunk = core::mem::replace(&mut self.chunk, self.leaf.clone());
+            self.push_cv(chunk.finalize(), first);
+
+            let leaf = &self.leaf;
+            let cvs: Vec<[u8; 32]> = batch
+                .par_chunks(Self::CHUNK_LEN)
+                .enumerate()
+                .map(|(i, data)| {
+                    let mut hasher = leaf_hasher(leaf, first + i as u64);
+                    hasher.update(data);
+                    hasher.finalize()
+                })
+                .collect();
+
+            // All but the last chunk of the batch are complete
+            let last = cvs.len() - 1;
+            for (i, cv) in cvs[..last].iter().enumerate() {
+                self.push_cv(*cv, first + i as u64 + 1);
+            }
+
+            self.chunk_index = first + last as u64;
+            self.chunk = leaf_hasher(&self.leaf, self.chunk_index);
+            self.chunk.update(&batch[last * Self::CHUNK_LEN..]);
+            self.chunk_len = Self::CHUNK_LEN;
+        }
+
+        self.update(rest);
+    }
+
+    /// Finalizes and returns the root hash (256-bit output)
+    pub fn finalize(self) -> [u8; 32] {
+        self.root().finalize()
+    }
+
+    /// Finalizes and returns the root hash (512-bit output)
+    pub fn finalize512(self) -> [u8; 64] {
+        self.root().finalize512()
+    }
+
+    /// Convenience method: tree-hash data in one shot on the current thread
+    pub fn hash(data: &[u8]) -> [u8; 32] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// Convenience method: tree-hash data in one shot in parallel
+    #[cfg(feature = "parallel")]
+    pub fn hash_parallel(data: &[u8]) -> [u8; 32] {
+        let mut hasher = Self::new();
+        hasher.update_parallel(data);
+        hasher.finalize()
+    }
+
+    /// Pushes the chaining value of a completed chunk
+    ///
+    /// `completed` is the number of chunks completed so far, including this
+    /// one; each trailing zero bit closes one subtree.
+    fn push_cv(&mut self, cv: [u8; 32], completed: u64) {
+        let mut cv = cv;
+        let mut total = completed;
+
+        while total & 1 == 0 {
+            self.stack_len -= 1;
+            cv = parent_hasher(&self.parent, &self.stack[self.stack_len], &cv).finalize();
+            total >>= 1;
+        }
+
+        self.stack[self.stack_len] = cv;
+        self.stack_len += 1;
+    }
+
+    /// Folds the stack into the root node, ready to be finalized
+    fn root(self) -> Fract {
+        let mut node = self.chunk;
+
+        for left in self.stack[..self.stack_len].iter().rev() {
+            node = parent_hasher(&self.parent, left, &node.finalize());
+        }
+
+        node.domain |= DOMAIN_TREE_ROOT;
+        node
+    }
+}
+
+impl Default for FractTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Starts the hasher for the leaf at `index`
+fn leaf_hasher(leaf: &Fract, index: u64) -> Fract {
+    let mut hasher = leaf.clone();
+    hasher.update(&index.to_le_bytes());
+    hasher
+}
+
+/// Returns the unfinalized hasher of a parent node
+fn parent_hasher(parent: &Fract, left: &[u8; 32], right: &[u8; 32]) -> Fract {
+    let mut hasher = parent.clone();
+    hasher.update(left);
+    hasher.update(right);
+    hasher
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHUNK_LEN: usize = FractTree::CHUNK_LEN;
+
+    /// Lengths around chunk and subtree boundaries
+    const LENGTHS: [usize; 11] = [
+        0,
+        1,
+        CHUNK_LEN - 1,
+        CHUNK_LEN,
+        CHUNK_LEN + 1,
+        2 * CHUNK_LEN,
+        3 * CHUNK_LEN + 5,
+        4 * CHUNK_LEN,
+        5 * CHUNK_LEN,
+        8 * CHUNK_LEN + 1,
+        13 * CHUNK_LEN - 7,
+    ];
+
+    fn input(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    /// Recursive definition of the tree, for checking the incremental stack
+    fn reference(data: &[u8]) -> [u8; 32] {
+        fn node(tree: &FractTree, data: &[u8], first: u64) -> Fract {
+            let chunks = data.len().div_ceil(CHUNK_LEN).max(1);
+            if chunks == 1 {
+                let mut hasher = leaf_hasher(&tree.leaf, first);
+                hasher.update(data);
+                return hasher;
+            }
+
+            let left_chunks = 1 << (usize::BITS - 1 - (chunks - 1).leading_zeros());
+            let (left, right) = data.split_at(left_chunks * CHUNK_LEN);
+            let left = node(tree, left, first).finalize();
+            let right = node(tree, right, first + left_chunks as u64).finalize();
+            parent_hasher(&tree.parent, &left, &right)
+        }
+
+        let mut root = node(&FractTree::new(), data, 0);
+        root.domain |= DOMAIN_TREE_ROOT;
+        root.finalize()
+    }
+
+    #[test]
+    fn test_vectors() {
+        assert_eq!(
+            hex::encode(FractTree::hash(b"")),
+            "4736ea53f208a757d89277b760697fd5ebfa4c6b3de5d28c90a5ac5e4151f40d"
+        );
+        assert_eq!(
+            hex::encode(FractTree::hash(b"hello world")),
+            "92763a1fbcb6b9f0f4eaab33cad1451f4360605d039225d119552e5ea3737132"
+        );
+        assert_eq!(
+            hex::encode(FractTree::hash(&[0x61; 3 * CHUNK_LEN])),
+            "9649f38107f27dee0d8bf6645c32e7bbad859f8467f0e40667af1827fe277baa"
+        );
+    }
+
+    #[test]
+    fn test_matches_reference() {
+        for len in LENGTHS {
+            let data = input(len);
+            assert_eq!(FractTree::hash(&data), reference(&data), "len {}", len);
+        }
+    }
+
+    #[test]
+    fn test_chunked_updates() {
+        for len in LENGTHS {
+            let data = input(len);
+            let mut hasher = FractTree::new();
+            for piece in data.chunks(1000) {
+                hasher.update(piece);
+            }
+            assert_eq!(hasher.finalize(), FractTree::hash(&data), "len {}", len);
+        }
+    }
+
+    #[test]
+    fn test_trailing_byte_changes_root() {
+        assert_ne!(FractTree::hash(b"abcdef"), FractTree::hash(b"abcdef\x01"));
+
+        // The leaf prefix shifts the block boundary; cover every final block length
+        for len in 0..48 {
+            let data = vec![0x61; len];
+            let mut extended = data.clone();
+            extended.push(0x01);
+            assert_ne!(
+                FractTree::hash(&data),
+                FractTree::hash(&extended),
+                "len {}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_domain_separated() {
+        assert_ne!(FractTree::hash(b"hello world"), Fract::hash(b"hello world"));
+
+        // A root never equals the chaining value a parent would use
+        let data = input(CHUNK_LEN);
+        let mut leaf = leaf_hasher(&FractTree::new().leaf, 0);
+        leaf.update(&data);
+        assert_ne!(FractTree::hash(&data), leaf.finalize());
+    }
+
+    #[test]
+    fn test_finalize512() {
+        let mut hasher = FractTree::new();
+        hasher.update(&input(3 * CHUNK_LEN));
+        let wide = hasher.clone().finalize512();
+        assert_eq!(wide[..32], hasher.finalize());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_matches_sequential() {
+        // The last length spans more than one parallel batch
+        for len in LENGTHS.into_iter().chain([1030 * CHUNK_LEN + 3]) {
+            let data = input(len);
+            let expected = FractTree::hash(&data);
+            assert_eq!(FractTree::hash_parallel(&data), expected, "len {}", len);
+
+            // Parallel updates starting mid-chunk and mid-stream
+            let mut hasher = FractTree::new();
+            let (head, tail) = data.split_at(len / 3);
+            hasher.update(&head[..head.len() / 2]);
+            hasher.update_parallel(&head[head.len() / 2..]);
+            hasher.update_parallel(tail);
+            assert_eq!(hasher.finalize(), expected, "len {}", len);
+        }
+    }
+}
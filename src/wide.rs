@@ -0,0 +1,248 @@
+//! FRACT-Wide: a 512-bit state with a 384-bit capacity
+//!
+//! [`Fract`](crate::Fract) keeps 128 of its 256 state bits as capacity, and
+//! generic sponge attacks cost about 2^(c/2): 2^64 for every output length,
+//! including `hash512`. [`FractWide`] runs the same HLTM over an eight-word
+//! coupled lattice and still absorbs 128 bits per block, leaving a 384-bit
+//! capacity, so generic attacks would cost 2^192.
+//!
+//! That bound assumes the round function behaves as an ideal permutation
+//! and that no structural attack exists. Neither is established: HLTM
+//! outputs are always ≡ 0 mod 4, and nothing yet shows that the round is
+//! invertible, so the state may not be a permutation at all.
+//!
+//! Lattice word `i` is coupled to words `i + 1` and `i + 3` (mod 8), the
+//! same offsets as the four-word lattice. A one-bit difference spreads to
+//! every word within 8 rounds (5 for the four-word lattice), so FRACT-Wide
+//! applies 16 rounds. Hashing is about three times slower than FRACT-256.
+
+use crate::permutation::hltm;
+
+/// Number of state words
+const WORDS: usize = 8;
+
+/// Rate in bytes: 128 bits (2 × u64)
+const RATE: usize = 16;
+
+/// Number of permutation rounds
+const ROUNDS: usize = 16;
+
+/// Right shifts applied to word `i + 1` when updating word `i`
+const SHIFT_RIGHT: [u32; WORDS] = [31, 23, 47, 13, 41, 7, 53, 19];
+
+/// Left shifts applied to word `i + 3` when updating word `i`
+const SHIFT_LEFT: [u32; WORDS] = [17, 11, 29, 5, 37, 3, 43, 59];
+
+/// Initialization Vector (fractional parts of the square roots of the first
+/// eight primes)
+const IV: [u64; WORDS] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// FRACT-Wide hasher state
+#[derive(Clone, Debug)]
+pub struct FractWide {
+    /// Internal state vector (8 × u64)
+    pub(crate) state: [u64; WORDS],
+    /// Buffer for absorbing data
+    pub(crate) buffer: [u8; RATE],
+    /// Number of bytes currently in buffer
+    pub(crate) buffer_len: usize,
+}
+
+impl FractWide {
+    /// Creates a new FRACT-Wide hasher
+    pub fn new() -> Self {
+        Self {
+            state: IV,
+            buffer: [0; RATE],
+            buffer_len: 0,
+        }
+    }
+
+    /// Absorbs data into the state
+    pub fn update(&mut self, data: &[u8]) {
+        let mut bytes = data;
+
+        // Fill buffer if not empty
+        if self.buffer_len > 0 {
+            let take = bytes.len().min(RATE - self.buffer_len);
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&bytes[..take]);
+            self.buffer_len += take;
+            bytes = &bytes[take..];
+
+            if self.buffer_len == RATE {
+                self.absorb_block();
+                self.buffer_len = 0;
+            }
+        }
+
+        // Process full blocks
+        while bytes.len() >= RATE {
+            self.buffer.copy_from_slice(&bytes[..RATE]);
+            self.absorb_block();
+            bytes = &bytes[RATE..];
+        }
+
+        // Store remaining bytes
+        if !bytes.is_empty() {
+            self.buffer[..bytes.len()].copy_from_slice(bytes);
+            self.buffer_len = bytes.len();
+        }
+    }
+
+    /// Finalizes and returns the 512-bit hash
+    pub fn finalize(mut self) -> [u8; 64] {
+        // 10*1 padding on the rate portion
+        self.buffer[self.buffer_len..].fill(0);
+        self.buffer[self.buffer_len] = 0x01;
+        self.buffer[RATE - 1] |= 0x80;
+        self.absorb_block();
+
+        let mut output = [0u8; 64];
+        for (i, block) in output.chunks_exact_mut(RATE).enumerate() {
+            if i > 0 {
+                self.permute();
+            }
+            block[..8].copy_from_slice(&self.state[0].to_le_bytes());
+            block[8..].copy_from_slice(&self.state[1].to_le_bytes());
+        }
+        output
+    }
+
+    /// Convenience method: hash data in one shot
+    pub fn hash(data: &[u8]) -> [u8; 64] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// XORs the buffer into the rate words and permutes
+    fn absorb_block(&mut self) {
+        let (low, high) = self.buffer.split_at(8);
+        self.state[0] ^= u64::from_le_bytes(low.try_into().unwrap());
+        self.state[1] ^= u64::from_le_bytes(high.try_into().unwrap());
+        self.permute();
+    }
+
+    /// Applies the permutation (16 rounds of the wide lattice)
+    fn permute(&mut self) {
+        for _ in 0..ROUNDS {
+            self.state = round(self.state);
+        }
+    }
+}
+
+impl Default for FractWide {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One round of the eight-word coupled lattice
+#[inline(always)]
+fn round(state: [u64; WORDS]) -> [u64; WORDS] {
+    let mut next = [0u64; WORDS];
+    for i in 0..WORDS {
+        let right = state[(i + 1) % WORDS] >> SHIFT_RIGHT[i];
+        let left = state[(i + 3) % WORDS] << SHIFT_LEFT[i];
+        next[i] = hltm(state[i]).wrapping_add(right ^ left);
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_answers() {
+        let vectors: [(&[u8], &str); 3] = [
+            (
+                b"",
+                "82378a7a6ee3917289ca69d4b31a237166b8df344ecbadd7299d45c86ea30cd1f13fbcfc6add271e930a4b75945d2b850772bd7c1787d5ed3ba98a8af1d3e3d3",
+            ),
+            (
+                b"abc",
+                "664672f245749553eb061cf2476529af94b3458cbc5824b45a95827b773792dd5ea7ed996d8b4bca25fc5e48ef5a1a32124348aff96c198753b6ab9eb9b57559",
+            ),
+            (
+                b"hello world",
+                "14ccea0ee0b36aeb5f93ca26d5d1aba2c4a4d6f4a3be91100ecdb3112a0b854068b19479ec5964906fc14933df04ebb385cf706da092221196dcc28fe3bb6697",
+            ),
+        ];
+
+        for (input, expected) in vectors {
+            assert_eq!(hex::encode(FractWide::hash(input)), expected);
+        }
+    }
+
+    #[test]
+    fn test_chunked_matches_one_shot() {
+        let data: Vec<u8> = (0..200u8).collect();
+        let expected = FractWide::hash(&data);
+
+        for split in [0, 1, 15, 16, 17, 100, 199, 200] {
+            let mut hasher = FractWide::new();
+            hasher.update(&data[..split]);
+            hasher.update(&data[split..]);
+            assert_eq!(hasher.finalize(), expected, "split {}", split);
+        }
+    }
+
+    #[test]
+    fn test_distinct_from_fract() {
+        assert_ne!(FractWide::hash(b"abc"), crate::Fract::hash512(b"abc"));
+        // Padding separates a trailing zero byte
+        assert_ne!(FractWide::hash(b"abc"), FractWide::hash(b"abc\0"));
+    }
+
+    #[test]
+    fn test_full_diffusion() {
+        // Any one-bit flip changes many bits of every word within half the rounds
+        for bit in 0..64 * WORDS {
+            let mut a = IV;
+            let mut b = IV;
+            b[bit / 64] ^= 1 << (bit % 64);
+
+            for _ in 0..ROUNDS / 2 {
+                a = round(a);
+                b = round(b);
+            }
+            for i in 0..WORDS {
+                let diff = (a[i] ^ b[i]).count_ones();
+                assert!(
+                    diff > 16,
+                    "bit {}: word {} differs in {} bits",
+                    bit,
+                    i,
+                    diff
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_avalanche() {
+        let h1 = FractWide::hash(b"test");
+        let h2 = FractWide::hash(b"tesu");
+
+        let diff_bits: u32 = h1
+            .iter()
+            .zip(h2.iter())
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum();
+        assert!(
+            diff_bits > 200,
+            "Poor avalanche effect: only {} bits differ",
+            diff_bits
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! `zeroize` support: wiping hasher state and buffers
+//!
+//! With the `zeroize` feature, [`Fract`] clears its state words, buffer
+//! and counters when dropped, and every type holding keyed state either
+//! wraps a `Fract` (and so is wiped with it) or clears its own output
+//! buffers as well. All of them implement `Zeroize` for wiping in place.
+//!
+//! [`FractState`] wipes its fields on drop too, but the arrays returned by
+//! [`FractState::to_bytes`] are plain copies; wrap them in
+//! `zeroize::Zeroizing` when the absorbed data is secret.
+//!
+//! [`FractPermutation`] is `Copy` and cannot have a destructor; it only
+//! implements `Zeroize`. Copies made by moves, and temporaries of the
+//! permutation, are not covered.
+
+#[cfg(feature = "rand_core")]
+use crate::FractRng;
+use crate::{
+    Fract, Fract512, FractDuplex, FractHasher, FractMac, FractPermutation, FractState, FractWide,
+    FractXof, RandomFractState,
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+impl Zeroize for Fract {
+    fn zeroize(&mut self) {
+        self.state.zeroize();
+        self.buffer.zeroize();
+        self.buffer_len.zeroize();
+        self.total_len.zeroize();
+        self.domain.zeroize();
+        self.initial_state.zeroize();
+        self.rounds.zeroize();
+    }
+}
+
+impl Drop for Fract {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Fract {}
+
+impl Zeroize for FractWide {
+    fn zeroize(&mut self) {
+        self.state.zeroize();
+        self.buffer.zeroize();
+        self.buffer_len.zeroize();
+    }
+}
+
+impl Drop for FractWide {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for FractWide {}
+
+impl Zeroize for FractState {
+    fn zeroize(&mut self) {
+        self.version.zeroize();
+        self.state.zeroize();
+        self.initial_state.zeroize();
+        self.domain.zeroize();
+        self.buffer.zeroize();
+        self.buffer_len.zeroize();
+        self.total_len.zeroize();
+        self.rounds.zeroize();
+    }
+}
+
+impl Drop for FractState {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for FractState {}
+
+impl Zeroize for FractXof {
+    fn zeroize(&mut self) {
+        self.sponge.zeroize();
+        self.block.zeroize();
+        self.pos.zeroize();
+    }
+}
+
+impl Drop for FractXof {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for FractXof {}
+
+#[cfg(feature = "rand_core")]
+impl Zeroize for FractRng {
+    fn zeroize(&mut self) {
+        self.duplex.zeroize();
+        self.buffer.zeroize();
+        self.pos.zeroize();
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl Drop for FractRng {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl ZeroizeOnDrop for FractRng {}
+
+impl Zeroize for FractPermutation {
+    fn zeroize(&mut self) {
+        self.state_mut().zeroize();
+    }
+}
+
+/// Implements `Zeroize` for a wrapper around one `Fract`, which already
+/// wipes itself on drop
+macro_rules! wraps_fract {
+    ($($ty:ty => $field:ident),* $(,)?) => {
+        $(
+            impl Zeroize for $ty {
+                fn zeroize(&mut self) {
+                    self.$field.zeroize();
+                }
+            }
+
+            impl ZeroizeOnDrop for $ty {}
+        )*
+    };
+}
+
+wraps_fract! {
+    Fract512 => inner,
+    FractMac => inner,
+    FractDuplex => sponge,
+    FractHasher => sponge,
+    RandomFractState => sponge,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zeroize_fract() {
+        let mut hasher = Fract::with_customization(b"app", b"secret");
+        hasher.update(b"sensitive");
+        hasher.zeroize();
+
+        assert_eq!(hasher.state, [0; 4]);
+        assert_eq!(hasher.initial_state, [0; 4]);
+        assert_eq!(hasher.buffer, [0; 16]);
+        assert_eq!(
+            (hasher.buffer_len, hasher.total_len, hasher.domain),
+            (0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_zeroize_keyed_types() {
+        let mut mac = FractMac::new(b"key");
+        mac.update(b"data");
+        mac.zeroize();
+        assert_eq!(mac.inner.state, [0; 4]);
+
+        let mut xof = Fract::new().finalize_xof();
+        xof.squeeze(&mut [0u8; 5]);
+        xof.zeroize();
+        assert_eq!(xof.sponge.state, [0; 4]);
+        assert_eq!(xof.block, [0; 16]);
+
+        let mut duplex = FractDuplex::new(b"protocol");
+        duplex.absorb(b"key");
+        duplex.zeroize();
+        assert_eq!(duplex.sponge.state, [0; 4]);
+
+        let mut midstate = FractMac::new(b"key").inner.export_state();
+        midstate.zeroize();
+        assert_eq!(midstate.state, [0; 4]);
+        assert_eq!(midstate.initial_state, [0; 4]);
+        assert_eq!((midstate.total_len, midstate.rounds), (0, 0));
+
+        let mut permutation = FractPermutation::new([1, 2, 3, 4]);
+        permutation.zeroize();
+        assert_eq!(permutation.state(), [0; 4]);
+    }
+
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn test_zeroize_rng() {
+        use rand_core::{RngCore, SeedableRng};
+
+        let mut rng = FractRng::seed_from_u64(1);
+        rng.next_u32();
+        rng.zeroize();
+        assert_eq!(rng.buffer, [0; 256]);
+        assert_eq!(rng.duplex.sponge.state, [0; 4]);
+    }
+
+    #[test]
+    fn test_drop_impls() {
+        fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+
+        assert_zeroize_on_drop::<Fract>();
+        assert_zeroize_on_drop::<Fract512>();
+        assert_zeroize_on_drop::<FractWide>();
+        assert_zeroize_on_drop::<FractState>();
+        assert_zeroize_on_drop::<FractMac>();
+        assert_zeroize_on_drop::<FractXof>();
+        assert_zeroize_on_drop::<FractDuplex>();
+        assert_zeroize_on_drop::<FractHasher>();
+        assert_zeroize_on_drop::<RandomFractState>();
+    }
+}
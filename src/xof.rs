@@ -0,0 +1,143 @@
+//! FRACT-XOF: extendable-output mode of the FRACT sponge
+//!
+//! Finalizing a [`Fract`] with [`Fract::finalize_xof`] yields a reader that
+//! squeezes an arbitrary number of bytes out of the sponge, one rate block
+//! per permutation. The final padding carries the XOF domain flag, so XOF
+//! output never shares a prefix with the fixed-length digests.
+
+use crate::{Fract, RATE};
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
+/// Extendable-output reader over a finalized FRACT sponge
+#[derive(Clone, Debug)]
+pub struct FractXof {
+    /// Finalized sponge the output is squeezed from
+    pub(crate) sponge: Fract,
+    /// Rate portion of the current state as bytes
+    pub(crate) block: [u8; RATE],
+    /// Number of bytes of `block` already returned
+    pub(crate) pos: usize,
+}
+
+impl FractXof {
+    /// Wraps a sponge whose final block has already been absorbed
+    pub(crate) fn new(sponge: Fract) -> Self {
+        let mut xof = Self {
+            sponge,
+            block: [0; RATE],
+            pos: 0,
+        };
+        xof.load_block();
+        xof
+    }
+
+    /// Fills `out` with the next `out.len()` bytes of output
+    ///
+    /// Can be called repeatedly; the concatenation of all squeezed bytes is
+    /// independent of how the output is split across calls.
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        let mut out = out;
+
+        while !out.is_empty() {
+            if self.pos == RATE {
+                self.sponge.permute();
+                self.load_block();
+            }
+
+            let take = (RATE - self.pos).min(out.len());
+            out[..take].copy_from_slice(&self.block[self.pos..self.pos + take]);
+            self.pos += take;
+            out = &mut out[take..];
+        }
+    }
+
+    /// Copies the rate portion of the state into the output block
+    fn load_block(&mut self) {
+        self.block = self.sponge.rate_bytes();
+        self.pos = 0;
+    }
+}
+
+#[cfg(feature = "std")]
+impl Read for FractXof {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.squeeze(buf);
+        Ok(buf.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xof(data: &[u8]) -> FractXof {
+        let mut hasher = Fract::new();
+        hasher.update(data);
+        hasher.finalize_xof()
+    }
+
+    #[test]
+    fn test_split_squeeze_matches_single_squeeze() {
+        let mut expected = [0u8; 100];
+        xof(b"hello cat").squeeze(&mut expected);
+
+        let mut reader = xof(b"hello cat");
+        let mut output = [0u8; 100];
+        let (a, rest) = output.split_at_mut(7);
+        let (b, c) = rest.split_at_mut(16);
+        reader.squeeze(a);
+        reader.squeeze(b);
+        reader.squeeze(c);
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_domain_separated_from_fixed_output() {
+        let mut output = [0u8; 64];
+        xof(b"hello world").squeeze(&mut output);
+        assert_ne!(output[..32], Fract::hash(b"hello world"));
+        assert_ne!(output, Fract::hash512(b"hello world"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_impl() {
+        let mut expected = [0u8; 48];
+        xof(b"reader").squeeze(&mut expected);
+
+        let mut output = [0u8; 48];
+        xof(b"reader").read_exact(&mut output).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_trailing_byte_changes_output() {
+        // m and m || 0x01 must not pad to the same final block
+        for len in 0..48 {
+            let message = vec![0x61; len];
+            let mut extended = message.clone();
+            extended.push(0x01);
+
+            let mut a = [0u8; 32];
+            let mut b = [0u8; 32];
+            xof(&message).squeeze(&mut a);
+            xof(&extended).squeeze(&mut b);
+            assert_ne!(a, b, "length {}", len);
+        }
+    }
+
+    #[test]
+    fn test_long_output_blocks_differ() {
+        let mut output = [0u8; 1024];
+        xof(b"").squeeze(&mut output);
+
+        let blocks: Vec<&[u8]> = output.chunks(RATE).collect();
+        for (i, a) in blocks.iter().enumerate() {
+            for b in &blocks[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Known-answer tests for the frozen v1 algorithms
+//!
+//! `tests/data/kat-v1.txt` pins the output of every [`Algorithm`]. A failure
+//! here means an output changed: that is never allowed under an existing
+//! identifier, so the change needs a new algorithm version instead.
+
+use fract::{Algorithm, Fract, FractTree, FractWide};
+
+const KAT: &str = include_str!("data/kat-v1.txt");
+
+struct Vector {
+    line: usize,
+    algorithm: Algorithm,
+    message: Vec<u8>,
+    digest: Vec<u8>,
+}
+
+fn parse_message(field: &str) -> Vec<u8> {
+    if field == "-" {
+        Vec::new()
+    } else if let Some(len) = field.strip_prefix("pattern:") {
+        let len: usize = len.parse().expect("pattern length");
+        (0..len).map(|i| (i % 251) as u8).collect()
+    } else {
+        hex::decode(field).expect("message hex")
+    }
+}
+
+fn vectors() -> Vec<Vector> {
+    KAT.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            assert_eq!(fields.len(), 3, "line {}: expected 3 fields", index + 1);
+            Vector {
+                line: index + 1,
+                algorithm: fields[0].parse().expect("known algorithm"),
+                message: parse_message(fields[1]),
+                digest: hex::decode(fields[2]).expect("digest hex"),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_known_answers() {
+    let vectors = vectors();
+
+    for vector in &vectors {
+        let mut out = vec![0u8; vector.algorithm.output_len()];
+        vector
+            .algorithm
+            .hash_into(&vector.message, &mut out)
+            .unwrap();
+        assert_eq!(
+            hex::encode(&out),
+            hex::encode(&vector.digest),
+            "line {}: {} output drifted",
+            vector.line,
+            vector.algorithm
+        );
+    }
+
+    // Every identifier is pinned by the same set of messages
+    let per_algorithm = vectors.len() / Algorithm::ALL.len();
+    assert!(per_algorithm > 0);
+    for algorithm in Algorithm::ALL {
+        let count = vectors.iter().filter(|v| v.algorithm == algorithm).count();
+        assert_eq!(count, per_algorithm, "{} vectors", algorithm);
+    }
+}
+
+#[test]
+fn test_known_answers_incremental() {
+    for vector in vectors() {
+        let chunks = vector.message.chunks(7);
+        let digest = match vector.algorithm {
+            Algorithm::Fract256V1 | Algorithm::Fract512V1 => {
+                let mut hasher = Fract::new();
+                chunks.for_each(|chunk| hasher.update(chunk));
+                if vector.algorithm.output_len() == 64 {
+                    hasher.finalize512().to_vec()
+                } else {
+                    hasher.finalize().to_vec()
+                }
+            }
+            Algorithm::Fract256TreeV1 | Algorithm::Fract512TreeV1 => {
+                let mut tree = FractTree::new();
+                chunks.for_each(|chunk| tree.update(chunk));
+                if vector.algorithm.output_len() == 64 {
+                    tree.finalize512().to_vec()
+                } else {
+                    tree.finalize().to_vec()
+                }
+            }
+            Algorithm::Fract512WideV1 => {
+                let mut hasher = FractWide::new();
+                chunks.for_each(|chunk| hasher.update(chunk));
+                hasher.finalize().to_vec()
+            }
+        };
+        assert_eq!(digest, vector.digest, "line {}", vector.line);
+    }
+}